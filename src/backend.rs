@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+use super::{AllocationLogEntry, Breakpoint, BreakpointHandler, BreakpointId, ClassCensus,
+            CompletionValue, DebuggerStatementHandler, ElementType, EnterFrameHandler, Environment,
+            EnvironmentType, ExceptionUnwindHandler, Fallible, Frame, FrameImplementation,
+            FrameType, FunctionKind, IntroductionType, NewScriptHandler, Object, ObjectQuery,
+            PopHandler, PromiseState, PropertyDescriptor, PropertyKey, SavedFrame, Script,
+            ScriptQuery, Source, SourceLocation, StepHandler, Symbol, Value, WeakEntry};
+
+/// A trait for engines that can back the wrapper types.
+///
+/// A backend defines one handle type per wrapper type. A wrapper owns a handle,
+/// and forwards each of its methods to the function of the same name on the
+/// backend, prefixed with the name of the wrapper. The handle of the wrapper
+/// on which the method was called is passed as the first argument; all other
+/// arguments are passed unchanged. Each function must behave as documented on
+/// the corresponding wrapper method, including the errors it returns.
+///
+/// `Memory` has no handle of its own. Instead, the handle of the debugger to
+/// which it belongs is passed to its functions.
+///
+/// A handle must refer to the same engine entity for as long as it lives, and
+/// two handles must compare equal if and only if they refer to the same
+/// entity.
+pub trait Backend: Sized + 'static {
+    /// The handle type of `Debugger`.
+    type Debugger: Clone + Eq + fmt::Debug;
+
+    /// The handle type of `Environment`.
+    type Environment: Clone + Eq + fmt::Debug;
+
+    /// The handle type of `Frame`.
+    type Frame: Clone + Eq + fmt::Debug;
+
+    /// The handle type of `Object`.
+    type Object: Clone + Eq + fmt::Debug;
+
+    /// The handle type of `Script`.
+    type Script: Clone + Eq + fmt::Debug;
+
+    /// The handle type of `Source`.
+    type Source: Clone + Eq + fmt::Debug;
+
+    /// The handle type of `Symbol`.
+    type Symbol: Clone + Eq + fmt::Debug;
+
+    // Methods of `Debugger`.
+    fn debugger_add_debuggee(debugger: &Self::Debugger, object: &Object<Self>) -> Object<Self>;
+    fn debugger_debugger_statement_handler(debugger: &Self::Debugger) -> Option<Rc<Box<dyn DebuggerStatementHandler<Self>>>>;
+    fn debugger_enter_frame_handler(debugger: &Self::Debugger) -> Option<Rc<Box<dyn EnterFrameHandler<Self>>>>;
+    fn debugger_exception_unwind_handler(debugger: &Self::Debugger) -> Option<Rc<Box<dyn ExceptionUnwindHandler<Self>>>>;
+    fn debugger_find_objects(debugger: &Self::Debugger, query: &ObjectQuery<Self>) -> Vec<Object<Self>>;
+    /// The wrapper only calls this with a valid `query`.
+    fn debugger_find_scripts(debugger: &Self::Debugger, query: &ScriptQuery<Self>) -> Vec<Script<Self>>;
+    fn debugger_find_sources(debugger: &Self::Debugger) -> Vec<Source<Self>>;
+    fn debugger_get_all_breakpoints(debugger: &Self::Debugger) -> Vec<Breakpoint<Self>>;
+    fn debugger_get_debuggees(debugger: &Self::Debugger) -> Vec<Object<Self>>;
+    fn debugger_get_newest_frame(debugger: &Self::Debugger) -> Option<Frame<Self>>;
+    fn debugger_has_debuggee(debugger: &Self::Debugger, object: &Object<Self>) -> bool;
+    fn debugger_is_enabled(debugger: &Self::Debugger) -> bool;
+    fn debugger_new_script_handler(debugger: &Self::Debugger) -> Option<Rc<Box<dyn NewScriptHandler<Self>>>>;
+    fn debugger_remove_debuggee(debugger: &Self::Debugger, object: &Object<Self>);
+    fn debugger_set_debugger_statement_handler(debugger: &Self::Debugger, handler: Option<Rc<Box<dyn DebuggerStatementHandler<Self>>>>);
+    fn debugger_set_enabled(debugger: &Self::Debugger, enabled: bool);
+    fn debugger_set_enter_frame_handler(debugger: &Self::Debugger, handler: Option<Rc<Box<dyn EnterFrameHandler<Self>>>>);
+    fn debugger_set_exception_unwind_handler(debugger: &Self::Debugger, handler: Option<Rc<Box<dyn ExceptionUnwindHandler<Self>>>>);
+    fn debugger_set_new_script_handler(debugger: &Self::Debugger, handler: Option<Rc<Box<dyn NewScriptHandler<Self>>>>);
+
+    // Methods of `Environment`.
+    fn environment_callee(environment: &Self::Environment) -> Fallible<Option<Object<Self>>>;
+    fn environment_find(environment: &Self::Environment, name: &str) -> Fallible<Option<Environment<Self>>>;
+    fn environment_get_variable(environment: &Self::Environment, name: &str) -> Fallible<Value<Self>>;
+    fn environment_is_inspectable(environment: &Self::Environment) -> bool;
+    fn environment_names(environment: &Self::Environment) -> Fallible<Vec<String>>;
+    fn environment_object(environment: &Self::Environment) -> Fallible<Option<Object<Self>>>;
+    fn environment_is_optimized_out(environment: &Self::Environment) -> Fallible<bool>;
+    fn environment_parent(environment: &Self::Environment) -> Fallible<Option<Environment<Self>>>;
+    fn environment_set_variable(environment: &Self::Environment, name: &str, value: &Value<Self>) -> Fallible<()>;
+    fn environment_get_type(environment: &Self::Environment) -> Fallible<EnvironmentType>;
+
+    // Methods of `Frame`.
+    fn frame_arguments(frame: &Self::Frame) -> Option<Vec<Value<Self>>>;
+    fn frame_callee(frame: &Self::Frame) -> Option<Object<Self>>;
+    fn frame_depth(frame: &Self::Frame) -> u32;
+    fn frame_is_constructing(frame: &Self::Frame) -> bool;
+    fn frame_environment(frame: &Self::Frame) -> Option<Environment<Self>>;
+    fn frame_eval(frame: &Self::Frame, code: &str) -> Fallible<CompletionValue<Self>>;
+    fn frame_eval_with_bindings(frame: &Self::Frame, code: &str, bindings: &BTreeMap<String, Value<Self>>) -> Fallible<CompletionValue<Self>>;
+    fn frame_get_type(frame: &Self::Frame) -> FrameType;
+    fn frame_implementation(frame: &Self::Frame) -> FrameImplementation;
+    fn frame_is_live(frame: &Self::Frame) -> bool;
+    fn frame_is_suspended(frame: &Self::Frame) -> bool;
+    fn frame_offset(frame: &Self::Frame) -> Option<u32>;
+    fn frame_older(frame: &Self::Frame) -> Option<Frame<Self>>;
+    fn frame_pop_handler(frame: &Self::Frame) -> Option<Rc<Box<dyn PopHandler<Self>>>>;
+    fn frame_step_handler(frame: &Self::Frame) -> Option<Rc<Box<dyn StepHandler<Self>>>>;
+    fn frame_script(frame: &Self::Frame) -> Option<Script<Self>>;
+    fn frame_set_pop_handler(frame: &Self::Frame, handler: Option<Rc<Box<dyn PopHandler<Self>>>>);
+    fn frame_set_step_handler(frame: &Self::Frame, handler: Option<Rc<Box<dyn StepHandler<Self>>>>);
+    fn frame_this(frame: &Self::Frame) -> Value<Self>;
+
+    // Methods of `Memory`.
+    fn memory_drain_allocations_log(debugger: &Self::Debugger) -> Vec<AllocationLogEntry>;
+    fn memory_is_recording_allocations(debugger: &Self::Debugger) -> bool;
+    fn memory_set_recording_allocations(debugger: &Self::Debugger, recording: bool);
+    fn memory_take_census(debugger: &Self::Debugger) -> BTreeMap<String, ClassCensus>;
+
+    // Methods of `Object`.
+    fn object_allocation_site(object: &Self::Object) -> Option<Vec<SavedFrame>>;
+    fn object_as_environment(object: &Self::Object) -> Fallible<Environment<Self>>;
+    fn object_bound_arguments(object: &Self::Object) -> Option<Vec<Value<Self>>>;
+    fn object_bound_target_function(object: &Self::Object) -> Option<Object<Self>>;
+    fn object_bound_this(object: &Self::Object) -> Value<Self>;
+    fn object_buffer(object: &Self::Object) -> Option<Object<Self>>;
+    fn object_byte_length(object: &Self::Object) -> Option<usize>;
+    fn object_byte_offset(object: &Self::Object) -> Option<usize>;
+    fn object_call(object: &Self::Object, this: &Value<Self>, arguments: &[Value<Self>]) -> Fallible<CompletionValue<Self>>;
+    fn object_construct(object: &Self::Object, arguments: &[Value<Self>]) -> Fallible<CompletionValue<Self>>;
+    fn object_class(object: &Self::Object) -> String;
+    fn object_collection_size(object: &Self::Object) -> Option<usize>;
+    fn object_display_name(object: &Self::Object) -> Option<String>;
+    /// The wrapper only calls this with a valid `descriptor`.
+    fn object_define_property(object: &Self::Object, key: &PropertyKey<Self>, descriptor: &PropertyDescriptor<Self>) -> Fallible<()>;
+    fn object_delete_property(object: &Self::Object, key: &PropertyKey<Self>) -> Fallible<()>;
+    fn object_element_type(object: &Self::Object) -> Option<ElementType>;
+    fn object_environment(object: &Self::Object) -> Option<Environment<Self>>;
+    fn object_error_location(object: &Self::Object) -> Option<SourceLocation>;
+    fn object_error_message(object: &Self::Object) -> Option<String>;
+    fn object_error_name(object: &Self::Object) -> Option<String>;
+    fn object_error_stack(object: &Self::Object) -> Option<Vec<SavedFrame>>;
+    fn object_execute_in_global(object: &Self::Object, code: &str) -> Fallible<CompletionValue<Self>>;
+    fn object_execute_in_global_with_bindings(object: &Self::Object, code: &str, bindings: &BTreeMap<String, Value<Self>>) -> Fallible<CompletionValue<Self>>;
+    fn object_freeze(object: &Self::Object) -> Fallible<()>;
+    fn object_function_kind(object: &Self::Object) -> Option<FunctionKind>;
+    fn object_get_own_property_descriptor(object: &Self::Object, key: &PropertyKey<Self>) -> Fallible<Option<PropertyDescriptor<Self>>>;
+    fn object_get_own_property_names(object: &Self::Object) -> Fallible<Vec<PropertyKey<Self>>>;
+    fn object_get_own_property_symbols(object: &Self::Object) -> Fallible<Vec<Symbol<Self>>>;
+    fn object_get_private_fields(object: &Self::Object) -> Vec<(String, Value<Self>)>;
+    fn object_get_private_methods(object: &Self::Object) -> Vec<(String, PropertyDescriptor<Self>)>;
+    fn object_get_prototype_of(object: &Self::Object) -> Option<Object<Self>>;
+    fn object_global(object: &Self::Object) -> Object<Self>;
+    fn object_is_arrow_function(object: &Self::Object) -> bool;
+    fn object_is_bound_function(object: &Self::Object) -> bool;
+    fn object_is_callable(object: &Self::Object) -> bool;
+    fn object_is_detached(object: &Self::Object) -> bool;
+    fn object_is_extensible(object: &Self::Object) -> Fallible<bool>;
+    fn object_is_frozen(object: &Self::Object) -> Fallible<bool>;
+    fn object_is_native(object: &Self::Object) -> bool;
+    fn object_is_proxy(object: &Self::Object) -> bool;
+    fn object_is_revoked_proxy(object: &Self::Object) -> bool;
+    fn object_is_sealed(object: &Self::Object) -> Fallible<bool>;
+    fn object_map_entries(object: &Self::Object, range: Range<usize>) -> Option<Vec<(Value<Self>, Value<Self>)>>;
+    fn object_name(object: &Self::Object) -> Option<String>;
+    fn object_parameter_names(object: &Self::Object) -> Option<Vec<String>>;
+    fn object_prevent_extensions(object: &Self::Object) -> Fallible<()>;
+    fn object_promise_allocation_site(object: &Self::Object) -> Option<Vec<SavedFrame>>;
+    fn object_promise_dependent_promises(object: &Self::Object) -> Option<Vec<Object<Self>>>;
+    fn object_promise_reason(object: &Self::Object) -> Option<Value<Self>>;
+    fn object_promise_resolution_site(object: &Self::Object) -> Option<Vec<SavedFrame>>;
+    fn object_promise_state(object: &Self::Object) -> Option<PromiseState>;
+    fn object_promise_value(object: &Self::Object) -> Option<Value<Self>>;
+    fn object_proxy_handler(object: &Self::Object) -> Option<Object<Self>>;
+    fn object_proxy_target(object: &Self::Object) -> Option<Object<Self>>;
+    fn object_read_bytes(object: &Self::Object, range: Range<usize>) -> Option<Vec<u8>>;
+    fn object_seal(object: &Self::Object) -> Fallible<()>;
+    fn object_script(object: &Self::Object) -> Option<Script<Self>>;
+    fn object_set_entries(object: &Self::Object, range: Range<usize>) -> Option<Vec<Value<Self>>>;
+    fn object_set_private_field(object: &Self::Object, name: &str, value: &Value<Self>) -> Fallible<()>;
+    fn object_weak_entries(object: &Self::Object, range: Range<usize>) -> Option<Vec<WeakEntry<Self>>>;
+    fn object_write_bytes(object: &Self::Object, offset: usize, bytes: &[u8]) -> Fallible<()>;
+
+    // Methods of `Script`.
+    fn script_clear_all_breakpoints(script: &Self::Script);
+    fn script_clear_breakpoints(script: &Self::Script, offset: u32) -> Fallible<()>;
+    fn script_display_name(script: &Self::Script) -> Option<String>;
+    fn script_get_all_line_offsets(script: &Self::Script) -> BTreeMap<u32, Vec<u32>>;
+    fn script_get_breakpoints(script: &Self::Script, offset: u32) -> Fallible<Vec<Breakpoint<Self>>>;
+    fn script_get_child_scripts(script: &Self::Script) -> Vec<Script<Self>>;
+    fn script_get_line_offsets(script: &Self::Script, line: u32) -> Vec<u32>;
+    fn script_get_offset_location(script: &Self::Script, offset: u32) -> Fallible<SourceLocation>;
+    fn script_global(script: &Self::Script) -> Object<Self>;
+    fn script_line_count(script: &Self::Script) -> u32;
+    fn script_remove_breakpoint(script: &Self::Script, id: BreakpointId) -> Fallible<()>;
+    fn script_set_breakpoint(script: &Self::Script, offset: u32, handler: Rc<Box<dyn BreakpointHandler<Self>>>) -> Fallible<BreakpointId>;
+    fn script_set_breakpoint_enabled(script: &Self::Script, id: BreakpointId, enabled: bool) -> Fallible<()>;
+    fn script_source(script: &Self::Script) -> Option<Source<Self>>;
+    fn script_source_length(script: &Self::Script) -> u32;
+    fn script_source_start(script: &Self::Script) -> u32;
+    fn script_start_line(script: &Self::Script) -> u32;
+    fn script_url(script: &Self::Script) -> String;
+
+    // Methods of `Source`.
+    fn source_canonical_id(source: &Self::Source) -> String;
+    fn source_element(source: &Self::Source) -> Option<Object<Self>>;
+    fn source_element_attribute_name(source: &Self::Source) -> Option<String>;
+    fn source_introduction_offset(source: &Self::Source) -> Option<u32>;
+    fn source_introduction_script(source: &Self::Source) -> Option<Script<Self>>;
+    fn source_introduction_type(source: &Self::Source) -> Option<IntroductionType>;
+    fn source_source_map_url(source: &Self::Source) -> Option<String>;
+    fn source_text(source: &Self::Source) -> String;
+    fn source_url(source: &Self::Source) -> String;
+
+    // Methods of `Symbol`.
+    fn symbol_description(symbol: &Self::Symbol) -> Option<String>;
+    fn symbol_registry_key(symbol: &Self::Symbol) -> Option<String>;
+}
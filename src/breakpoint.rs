@@ -0,0 +1,885 @@
+//! Ready-made breakpoint handlers.
+//!
+//! Each handler in this module can be passed to `Script::set_breakpoint`.
+//! A `Condition` pauses only when a condition evaluates to a truthy value, a
+//! `HitCount` pauses only on certain hits, and a `LogPoint` logs a message
+//! without ever pausing. Pausing is done by calling a `PauseHandler`, as for
+//! the steps in the `stepping` module.
+//!
+//! Conditions and log messages are evaluated with `Frame::eval`, in the frame
+//! that hit the breakpoint. If an evaluation fails, throws, is terminated, or
+//! is suspended, the failure is passed to an `EvalErrorHandler`, and the
+//! breakpoint neither pauses nor logs a message for that hit.
+//!
+//! A `Registry` holds breakpoints by url and line, so that they can be set
+//! before the scripts they refer to are loaded. It binds each of them to every
+//! matching script, both those that are already loaded and those loaded later,
+//! including scripts loaded again from the same url after a reload. Bindings
+//! to scripts whose global is no longer a debuggee, such as those of the page
+//! before a reload, are dropped the next time a script is loaded.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::num::NonZeroU32;
+use std::rc::{Rc, Weak};
+
+use stepping::PauseHandler;
+use {Backend, Breakpoint, BreakpointHandler, BreakpointId, CompletionValue, Debugger, Error,
+     Fallible, Frame, NewScriptHandler, ResumptionValue, Script, ScriptQuery, Value, descendants};
+
+/// An enum describing why the evaluation of a condition or of an expression in
+/// a log message did not produce a value.
+pub enum EvalError<B: Backend> {
+    /// The evaluation failed with the given error.
+    Error(Error),
+
+    /// The evaluation threw the given value as an exception.
+    Throw(Value<B>),
+
+    /// The evaluation was terminated.
+    Terminate,
+
+    /// The evaluation was suspended at a `yield` or `await` of the given
+    /// value.
+    Suspend(Value<B>)
+}
+
+impl<B: Backend> Clone for EvalError<B> {
+    fn clone(&self) -> EvalError<B> {
+        match self {
+            EvalError::Error(error) => EvalError::Error(*error),
+            EvalError::Throw(value) => EvalError::Throw(value.clone()),
+            EvalError::Terminate => EvalError::Terminate,
+            EvalError::Suspend(value) => EvalError::Suspend(value.clone())
+        }
+    }
+}
+
+impl<B: Backend> PartialEq for EvalError<B> {
+    fn eq(&self, other: &EvalError<B>) -> bool {
+        match (self, other) {
+            (EvalError::Error(a), EvalError::Error(b)) => a == b,
+            (EvalError::Throw(a), EvalError::Throw(b)) => a == b,
+            (EvalError::Terminate, EvalError::Terminate) => true,
+            (EvalError::Suspend(a), EvalError::Suspend(b)) => a == b,
+            _ => false
+        }
+    }
+}
+
+impl<B: Backend> fmt::Debug for EvalError<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::Error(error) => formatter.debug_tuple("Error").field(error).finish(),
+            EvalError::Throw(value) => formatter.debug_tuple("Throw").field(value).finish(),
+            EvalError::Terminate => formatter.write_str("Terminate"),
+            EvalError::Suspend(value) => formatter.debug_tuple("Suspend").field(value).finish()
+        }
+    }
+}
+
+/// A trait for values that can be used as eval error handler.
+pub trait EvalErrorHandler<B: Backend> {
+    fn handle(&self, frame: &Frame<B>, code: &str, error: &EvalError<B>);
+}
+
+/// A trait for values that can be used as log handler.
+pub trait LogHandler<B: Backend> {
+    fn handle(&self, frame: &Frame<B>, message: &str);
+}
+
+/// A breakpoint handler that pauses only when a condition evaluates to a
+/// truthy value.
+pub struct Condition<B: Backend> {
+    condition: String,
+    handler: Rc<Box<dyn PauseHandler<B>>>,
+    error_handler: Rc<Box<dyn EvalErrorHandler<B>>>
+}
+
+impl<B: Backend> Condition<B> {
+    /// Returns a breakpoint handler that evaluates the given `condition` each
+    /// time the breakpoint is hit, and calls the `handle` method of the given
+    /// `handler` if the result is truthy. If the evaluation does not produce a
+    /// value, the `handle` method of the given `error_handler` is called
+    /// instead.
+    pub fn new(condition: &str, handler: Rc<Box<dyn PauseHandler<B>>>,
+               error_handler: Rc<Box<dyn EvalErrorHandler<B>>>) -> Condition<B> {
+        Condition {
+            condition: condition.to_owned(),
+            handler,
+            error_handler
+        }
+    }
+
+    /// Returns the condition of the breakpoint handler.
+    pub fn condition(&self) -> &str {
+        &self.condition
+    }
+}
+
+impl<B: Backend> BreakpointHandler<B> for Condition<B> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B> {
+        match eval(frame, &self.condition) {
+            Ok(ref value) if value.is_truthy() => self.handler.handle(frame),
+            Ok(_) => None,
+            Err(error) => {
+                self.error_handler.handle(frame, &self.condition, &error);
+                None
+            }
+        }
+    }
+}
+
+/// An enum describing on which hits a `HitCount` pauses. Hits are counted
+/// from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HitCondition {
+    /// Pause on the given hit only.
+    Equal(NonZeroU32),
+
+    /// Pause on every hit that is a multiple of the given number.
+    Multiple(NonZeroU32)
+}
+
+/// A breakpoint handler that counts how often the breakpoint is hit, and
+/// pauses only on the hits given by a `HitCondition`.
+pub struct HitCount<B: Backend> {
+    condition: HitCondition,
+    hits: Cell<u32>,
+    handler: Rc<Box<dyn PauseHandler<B>>>
+}
+
+impl<B: Backend> HitCount<B> {
+    /// Returns a breakpoint handler that calls the `handle` method of the
+    /// given `handler` on the hits given by the given `condition`.
+    pub fn new(condition: HitCondition, handler: Rc<Box<dyn PauseHandler<B>>>) -> HitCount<B> {
+        HitCount {
+            condition,
+            hits: Cell::new(0),
+            handler
+        }
+    }
+
+    /// Returns the hit condition of the breakpoint handler.
+    pub fn condition(&self) -> HitCondition {
+        self.condition
+    }
+
+    /// Returns how often the breakpoint was hit so far.
+    pub fn hits(&self) -> u32 {
+        self.hits.get()
+    }
+
+    /// Resets the number of hits to 0.
+    pub fn reset(&self) {
+        self.hits.set(0);
+    }
+}
+
+impl<B: Backend> BreakpointHandler<B> for HitCount<B> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B> {
+        let hits = self.hits.get().saturating_add(1);
+        self.hits.set(hits);
+        let pause = match self.condition {
+            HitCondition::Equal(count) => hits == count.get(),
+            HitCondition::Multiple(count) => hits % count.get() == 0
+        };
+        if pause {
+            self.handler.handle(frame)
+        } else {
+            None
+        }
+    }
+}
+
+/// An identifier for a breakpoint in a `Registry`, as returned by
+/// `Registry::set_breakpoint`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PendingBreakpointId(pub u64);
+
+/// A trait for values that can be used as bind handler.
+pub trait BindHandler<B: Backend> {
+    fn handle(&self, id: PendingBreakpointId, breakpoint: &Breakpoint<B>);
+}
+
+struct Pending<B: Backend> {
+    url: String,
+    line: u32,
+    column: Option<u32>,
+    handler: Rc<Box<dyn BreakpointHandler<B>>>,
+    bindings: Vec<(Script<B>, BreakpointId)>
+}
+
+struct RegistryState<B: Backend> {
+    debugger: Debugger<B>,
+    bind_handler: Rc<Box<dyn BindHandler<B>>>,
+    pending: RefCell<BTreeMap<PendingBreakpointId, Pending<B>>>,
+    next_id: Cell<u64>,
+    open: Cell<bool>
+}
+
+/// A registry of breakpoints that are given by url, line and, optionally,
+/// column, rather than by script and offset.
+///
+/// Each breakpoint in the registry is bound to every script in the debuggees
+/// of its debugger whose url matches, and which has line entry points for its
+/// line. If a column is given, it is bound only at the line entry points with
+/// the smallest column that is not less than the given one, or, if there are
+/// none, at those with the greatest column. Otherwise, it is bound at all line
+/// entry points for its line. Each time a breakpoint is bound, the bind
+/// handler of the registry is called.
+///
+/// Each time a script is loaded, the bindings to scripts whose global is no
+/// longer a debuggee of the debugger are dropped, and the breakpoints to which
+/// they refer are cleared. Bindings to scripts in a global that is still a
+/// debuggee are kept, even if a script is loaded again from the same url into
+/// that global, since the functions of the older script may still be called.
+///
+/// While the registry is open, it replaces the new script handler of its
+/// debugger, which it calls once it has bound its breakpoints to the new
+/// script. Dropping the registry closes it.
+pub struct Registry<B: Backend> {
+    state: Rc<RegistryState<B>>,
+    /// The new script handler that the registry replaced, if any.
+    new_script_handler: Option<Rc<Box<dyn NewScriptHandler<B>>>>,
+    /// The new script handler that the registry installed.
+    binder: Rc<Box<dyn NewScriptHandler<B>>>
+}
+
+impl<B: Backend> Registry<B> {
+    /// Returns an empty registry for the given `debugger`. The `handle` method
+    /// of the given `bind_handler` is called each time a breakpoint in the
+    /// registry is bound.
+    pub fn new(debugger: &Debugger<B>, bind_handler: Rc<Box<dyn BindHandler<B>>>) -> Registry<B> {
+        let state = Rc::new(RegistryState {
+            debugger: debugger.clone(),
+            bind_handler,
+            pending: RefCell::new(BTreeMap::new()),
+            next_id: Cell::new(0),
+            open: Cell::new(true)
+        });
+        let new_script_handler = debugger.new_script_handler();
+        let binder: Rc<Box<dyn NewScriptHandler<B>>> = Rc::new(Box::new(Binder {
+            state: Rc::downgrade(&state),
+            handler: new_script_handler.clone()
+        }));
+        debugger.set_new_script_handler(Some(binder.clone()));
+        Registry {
+            state,
+            new_script_handler,
+            binder
+        }
+    }
+
+    /// Closes the registry. This clears all the breakpoints in the registry,
+    /// and restores the new script handler of the debugger, unless it was
+    /// replaced again since. Does nothing if the registry is already closed.
+    pub fn close(&self) {
+        if !self.state.open.replace(false) {
+            return;
+        }
+        let pending = self.state.pending.replace(BTreeMap::new());
+        for pending in pending.values() {
+            unbind(pending);
+        }
+        let debugger = &self.state.debugger;
+        if debugger.new_script_handler().map_or(false, |handler| Rc::ptr_eq(&handler, &self.binder)) {
+            debugger.set_new_script_handler(self.new_script_handler.clone());
+        }
+    }
+
+    /// Returns `true` if the registry is still open. Returns `false`
+    /// otherwise.
+    pub fn is_open(&self) -> bool {
+        self.state.open.get()
+    }
+
+    /// Returns the scripts and identifiers of the breakpoints to which the
+    /// breakpoint with the given `id` in the registry is currently bound.
+    ///
+    /// # Errors
+    /// If the registry has no breakpoint with the given `id`, returns
+    /// `BreakpointNotFound`.
+    pub fn get_bindings(&self, id: PendingBreakpointId) -> Fallible<Vec<(Script<B>, BreakpointId)>> {
+        let pending = self.state.pending.borrow();
+        let pending = pending.get(&id).ok_or(Error::BreakpointNotFound)?;
+        Ok(pending.bindings.clone())
+    }
+
+    /// Removes the breakpoint with the given `id` from the registry, and
+    /// clears the breakpoints to which it is bound.
+    ///
+    /// # Errors
+    /// If the registry has no breakpoint with the given `id`, returns
+    /// `BreakpointNotFound`.
+    pub fn remove_breakpoint(&self, id: PendingBreakpointId) -> Fallible<()> {
+        let pending = self.state.pending.borrow_mut().remove(&id).ok_or(Error::BreakpointNotFound)?;
+        unbind(&pending);
+        Ok(())
+    }
+
+    /// Adds a breakpoint at the given `line` and, optionally, `column` in the
+    /// document with the given `url` to the registry, and returns its
+    /// identifier. When the breakpoint is hit, the `handle` method of the
+    /// given `handler` will be called. The breakpoint is bound to the matching
+    /// scripts that are already loaded before this returns. If the registry
+    /// is closed, does nothing and returns `None` instead.
+    pub fn set_breakpoint(&self, url: &str, line: u32, column: Option<u32>,
+                          handler: Rc<Box<dyn BreakpointHandler<B>>>) -> Option<PendingBreakpointId> {
+        if !self.state.open.get() {
+            return None;
+        }
+        let id = PendingBreakpointId(self.state.next_id.get());
+        self.state.next_id.set(id.0 + 1);
+        self.state.pending.borrow_mut().insert(id, Pending {
+            url: url.to_owned(),
+            line,
+            column,
+            handler,
+            bindings: Vec::new()
+        });
+        let query = ScriptQuery {
+            url: Some(url.to_owned()),
+            ..ScriptQuery::new()
+        };
+        let scripts = self.state.debugger.find_scripts(&query).unwrap_or_default();
+        bind(&self.state, Some(id), &scripts);
+        Some(id)
+    }
+}
+
+impl<B: Backend> Drop for Registry<B> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Binds the breakpoint with the given `id` in the registry, or all its
+/// breakpoints if `id` is `None`, to the matching scripts among the given
+/// `scripts`, and then calls the bind handler for each new binding.
+fn bind<B: Backend>(state: &RegistryState<B>, id: Option<PendingBreakpointId>, scripts: &[Script<B>]) {
+    let mut bound = Vec::new();
+    {
+        let mut pending = state.pending.borrow_mut();
+        for (&pending_id, pending) in pending.iter_mut() {
+            if id.map_or(false, |id| id != pending_id) {
+                continue;
+            }
+            let scripts = scripts.iter().filter(|script| script.url() == pending.url);
+            for (script, offset) in resolve(scripts, pending.line, pending.column) {
+                if let Ok(breakpoint_id) = script.set_breakpoint(offset, pending.handler.clone()) {
+                    pending.bindings.push((script.clone(), breakpoint_id));
+                    bound.push((pending_id, Breakpoint {
+                        id: breakpoint_id,
+                        script,
+                        offset,
+                        line: pending.line,
+                        enabled: true,
+                        handler: pending.handler.clone()
+                    }));
+                }
+            }
+        }
+    }
+    for (id, breakpoint) in bound {
+        state.bind_handler.handle(id, &breakpoint);
+    }
+}
+
+/// Clears the breakpoints to which the given `pending` breakpoint is bound.
+fn unbind<B: Backend>(pending: &Pending<B>) {
+    for (script, id) in &pending.bindings {
+        // The breakpoint may have been cleared by someone else in the meantime.
+        let _ = script.remove_breakpoint(*id);
+    }
+}
+
+/// Drops the bindings of the breakpoints in the registry to scripts whose
+/// global is no longer a debuggee of its debugger, and clears the breakpoints
+/// to which they refer.
+fn prune<B: Backend>(state: &RegistryState<B>) {
+    for pending in state.pending.borrow_mut().values_mut() {
+        pending.bindings.retain(|(script, id)| {
+            let live = state.debugger.has_debuggee(&script.global());
+            if !live {
+                // The breakpoint may have been cleared by someone else in the
+                // meantime.
+                let _ = script.remove_breakpoint(*id);
+            }
+            live
+        });
+    }
+}
+
+/// Returns the scripts and offsets among the given `scripts` at which to bind
+/// a breakpoint at the given `line` and `column`.
+fn resolve<'a, B: Backend + 'a, I>(scripts: I, line: u32, column: Option<u32>) -> Vec<(Script<B>, u32)>
+    where I: Iterator<Item = &'a Script<B>>
+{
+    let entry_points = scripts.flat_map(|script| {
+        script.get_line_offsets(line).into_iter().map(move |offset| (script.clone(), offset))
+    });
+    let column = match column {
+        Some(column) => column,
+        None => return entry_points.collect()
+    };
+    let entry_points: Vec<(Script<B>, u32, u32)> = entry_points.filter_map(|(script, offset)| {
+        let location = script.get_offset_location(offset).ok()?;
+        Some((script, offset, location.column))
+    }).collect();
+    let columns = entry_points.iter().map(|&(_, _, column)| column);
+    let column = columns.clone().filter(|&entry_column| entry_column >= column).min()
+                        .or_else(|| columns.max());
+    entry_points.into_iter().filter(|&(_, _, entry_column)| Some(entry_column) == column)
+                .map(|(script, offset, _)| (script, offset)).collect()
+}
+
+struct Binder<B: Backend> {
+    /// The state of the registry, held weakly since the registry holds the
+    /// debugger, which holds this handler.
+    state: Weak<RegistryState<B>>,
+    /// The new script handler that this handler replaced, if any.
+    handler: Option<Rc<Box<dyn NewScriptHandler<B>>>>
+}
+
+impl<B: Backend> NewScriptHandler<B> for Binder<B> {
+    fn handle(&self, script: &Script<B>) {
+        if let Some(state) = self.state.upgrade() {
+            if state.open.get() {
+                prune(&state);
+                bind(&state, None, &descendants(script));
+            }
+        }
+        if let Some(ref handler) = self.handler {
+            handler.handle(script);
+        }
+    }
+}
+
+enum Part {
+    Text(String),
+    Code(String)
+}
+
+/// A breakpoint handler that logs a message each time the breakpoint is hit,
+/// without pausing.
+///
+/// The message is given by a template, in which each expression between
+/// braces is replaced by its value. Literal braces are written as `{{` and
+/// `}}`. Primitive values are converted to strings as in the debuggee. To
+/// avoid running debuggee code, errors are converted to their name and
+/// message, and other objects to `[object ` followed by their class and `]`.
+pub struct LogPoint<B: Backend> {
+    parts: Vec<Part>,
+    handler: Rc<Box<dyn LogHandler<B>>>,
+    error_handler: Rc<Box<dyn EvalErrorHandler<B>>>
+}
+
+impl<B: Backend> LogPoint<B> {
+    /// Returns a breakpoint handler that calls the `handle` method of the
+    /// given `handler` with the message given by the given `template` each
+    /// time the breakpoint is hit. If the evaluation of an expression in the
+    /// template does not produce a value, the `handle` method of the given
+    /// `error_handler` is called instead.
+    ///
+    /// # Errors
+    /// If the given `template` has an unmatched brace, or an empty expression,
+    /// returns `TemplateNotValid`.
+    pub fn new(template: &str, handler: Rc<Box<dyn LogHandler<B>>>,
+               error_handler: Rc<Box<dyn EvalErrorHandler<B>>>) -> Fallible<LogPoint<B>> {
+        Ok(LogPoint {
+            parts: parse(template)?,
+            handler,
+            error_handler
+        })
+    }
+}
+
+impl<B: Backend> BreakpointHandler<B> for LogPoint<B> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B> {
+        let mut message = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => message.push_str(text),
+                Part::Code(code) => match eval(frame, code) {
+                    Ok(value) => message.push_str(&value.to_string()),
+                    Err(error) => {
+                        self.error_handler.handle(frame, code, &error);
+                        return None;
+                    }
+                }
+            }
+        }
+        self.handler.handle(frame, &message);
+        None
+    }
+}
+
+/// Splits the given `template` into text and code parts.
+fn parse(template: &str) -> Fallible<Vec<Part>> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            },
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            },
+            '{' => {
+                let mut code = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some('{') | None => return Err(Error::TemplateNotValid),
+                        Some(c) => code.push(c)
+                    }
+                }
+                if code.trim().is_empty() {
+                    return Err(Error::TemplateNotValid);
+                }
+                if !text.is_empty() {
+                    parts.push(Part::Text(text.split_off(0)));
+                }
+                parts.push(Part::Code(code));
+            },
+            '}' => return Err(Error::TemplateNotValid),
+            c => text.push(c)
+        }
+    }
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    Ok(parts)
+}
+
+/// Evaluates the given `code` in the given `frame`.
+fn eval<B: Backend>(frame: &Frame<B>, code: &str) -> Result<Value<B>, EvalError<B>> {
+    match frame.eval(code) {
+        Ok(CompletionValue::Return(value)) => Ok(value),
+        Ok(CompletionValue::Throw(value)) => Err(EvalError::Throw(value)),
+        Ok(CompletionValue::Terminate) => Err(EvalError::Terminate),
+        Ok(CompletionValue::Yield(value)) | Ok(CompletionValue::Await(value)) => Err(EvalError::Suspend(value)),
+        Err(error) => Err(EvalError::Error(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::num::NonZeroU32;
+    use std::rc::Rc;
+
+    use reference::{Op, Reference, Runtime, ScriptBuilder, SourceBuilder};
+    use stepping::PauseHandler;
+    use {Breakpoint, BreakpointHandler, CompletionValue, Debugger, DebuggerStatementHandler, Error, Frame,
+         NewScriptHandler, Object, ResumptionValue, Script};
+
+    use super::{eval, parse, BindHandler, Condition, EvalError, EvalErrorHandler, HitCondition, HitCount, LogHandler,
+                LogPoint, PendingBreakpointId, Registry};
+
+    /// A handler that records each call in a shared log.
+    #[derive(Clone)]
+    struct Recorder {
+        log: Rc<RefCell<Vec<String>>>
+    }
+
+    impl Recorder {
+        fn new() -> Recorder {
+            Recorder { log: Rc::new(RefCell::new(Vec::new())) }
+        }
+
+        fn record(&self, entry: String) {
+            self.log.borrow_mut().push(entry);
+        }
+
+        fn log(&self) -> Vec<String> {
+            self.log.borrow().clone()
+        }
+    }
+
+    impl BindHandler<Reference> for Recorder {
+        fn handle(&self, id: PendingBreakpointId, breakpoint: &Breakpoint<Reference>) {
+            self.record(format!("bind {} at {}", id.0, breakpoint.offset));
+        }
+    }
+
+    impl BreakpointHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("breakpoint at {}", frame.offset().unwrap()));
+            None
+        }
+    }
+
+    impl EvalErrorHandler<Reference> for Recorder {
+        fn handle(&self, _frame: &Frame<Reference>, code: &str, error: &EvalError<Reference>) {
+            self.record(format!("error {} {:?}", code, error));
+        }
+    }
+
+    impl LogHandler<Reference> for Recorder {
+        fn handle(&self, _frame: &Frame<Reference>, message: &str) {
+            self.record(format!("log {}", message));
+        }
+    }
+
+    impl NewScriptHandler<Reference> for Recorder {
+        fn handle(&self, script: &Script<Reference>) {
+            self.record(format!("new script {}", script.url()));
+        }
+    }
+
+    impl PauseHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("pause {:?}", frame.eval("a").unwrap()));
+            None
+        }
+    }
+
+    /// A breakpoint handler that shares a `HitCount`, so that it can be
+    /// inspected and reset while the breakpoint is set.
+    struct Shared(Rc<HitCount<Reference>>);
+
+    impl BreakpointHandler<Reference> for Shared {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.0.handle(frame)
+        }
+    }
+
+    /// A breakpoint handler that evaluates code with `eval`, and records the
+    /// result in a shared log.
+    struct Evaluator(&'static str, Recorder);
+
+    impl BreakpointHandler<Reference> for Evaluator {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.1.record(format!("{:?}", eval(frame, self.0)));
+            None
+        }
+    }
+
+    /// A debugger statement handler that terminates the code that hit it.
+    struct Terminator;
+
+    impl DebuggerStatementHandler<Reference> for Terminator {
+        fn handle(&self, _frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            Some(CompletionValue::Terminate)
+        }
+    }
+
+    /// Returns a script that defines a function `f`, and then calls it once
+    /// with each of the given `arguments`, along with the body of `f`.
+    fn call_script(runtime: &Runtime, arguments: &[&str]) -> (Script<Reference>, Script<Reference>) {
+        let global = runtime.new_global();
+        let debugger = runtime.new_debugger();
+        debugger.add_debuggee(&global);
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Terminator))));
+        let mut builder = ScriptBuilder::new(1)
+            .function(1, "f", &["a"], ScriptBuilder::new(2)
+                .op(2, Op::Return("a".to_owned())))
+            .function(3, "g", &[], ScriptBuilder::new(4)
+                .op(4, Op::Debugger));
+        for (line, argument) in arguments.iter().enumerate() {
+            builder = builder.op(line as u32 + 6, Op::Expression(format!("f({})", argument)));
+        }
+        let source = runtime.new_source(SourceBuilder::new("test.js", ""));
+        let script = runtime.new_script(&global, &source, builder);
+        let body = script.get_child_scripts()[0].clone();
+        (script, body)
+    }
+
+    #[test]
+    fn condition_pauses_only_when_truthy() {
+        let runtime = Runtime::new();
+        let recorder = Recorder::new();
+        let (script, body) = call_script(&runtime, &["0", "1", "\"\"", "\"x\"", "null"]);
+        body.set_breakpoint(0, Rc::new(Box::new(Condition::new("a", Rc::new(Box::new(recorder.clone())),
+                                                               Rc::new(Box::new(recorder.clone())))))).unwrap();
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec![
+            "pause Return(Number(1.0))",
+            "pause Return(String(\"x\"))"
+        ]);
+    }
+
+    #[test]
+    fn condition_that_throws_does_not_pause() {
+        let runtime = Runtime::new();
+        let recorder = Recorder::new();
+        let (script, body) = call_script(&runtime, &["1"]);
+        body.set_breakpoint(0, Rc::new(Box::new(Condition::new("a.b.c", Rc::new(Box::new(recorder.clone())),
+                                                               Rc::new(Box::new(recorder.clone())))))).unwrap();
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec![
+            "error a.b.c Throw(String(\"TypeError: cannot read property c of undefined\"))"
+        ]);
+    }
+
+    #[test]
+    fn hit_count_pauses_on_the_given_hits() {
+        let runtime = Runtime::new();
+        let recorder = Recorder::new();
+        let (script, body) = call_script(&runtime, &["1", "2", "3", "4", "5"]);
+        let equal = Rc::new(HitCount::new(HitCondition::Equal(NonZeroU32::new(2).unwrap()),
+                                          Rc::new(Box::new(recorder.clone()))));
+        body.set_breakpoint(0, Rc::new(Box::new(Shared(equal.clone())))).unwrap();
+        let multiple = Rc::new(HitCount::new(HitCondition::Multiple(NonZeroU32::new(2).unwrap()),
+                                             Rc::new(Box::new(recorder.clone()))));
+        body.set_breakpoint(0, Rc::new(Box::new(Shared(multiple.clone())))).unwrap();
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec![
+            "pause Return(Number(2.0))",
+            "pause Return(Number(2.0))",
+            "pause Return(Number(4.0))"
+        ]);
+        assert_eq!((equal.hits(), multiple.hits()), (5, 5));
+        equal.reset();
+        assert_eq!(equal.hits(), 0);
+        runtime.execute(&script);
+        assert_eq!(recorder.log()[3..], [
+            "pause Return(Number(1.0))",
+            "pause Return(Number(2.0))",
+            "pause Return(Number(3.0))",
+            "pause Return(Number(5.0))"
+        ]);
+    }
+
+    #[test]
+    fn log_point_replaces_expressions_and_escaped_braces() {
+        let runtime = Runtime::new();
+        let recorder = Recorder::new();
+        let (script, body) = call_script(&runtime, &["1", "\"x\""]);
+        body.set_breakpoint(0, Rc::new(Box::new(LogPoint::new("{{a}} = {a}}}", Rc::new(Box::new(recorder.clone())),
+                                                              Rc::new(Box::new(recorder.clone()))).unwrap()))).unwrap();
+        body.set_breakpoint(0, Rc::new(Box::new(LogPoint::new("{a.b.c}", Rc::new(Box::new(recorder.clone())),
+                                                              Rc::new(Box::new(recorder.clone()))).unwrap()))).unwrap();
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec![
+            "log {a} = 1}",
+            "error a.b.c Throw(String(\"TypeError: cannot read property c of undefined\"))",
+            "log {a} = x}",
+            "error a.b.c Throw(String(\"TypeError: cannot read property c of undefined\"))"
+        ]);
+    }
+
+    #[test]
+    fn parse_rejects_unmatched_braces_and_empty_expressions() {
+        assert_eq!(parse("{{a}} {a}").map(|parts| parts.len()), Ok(2));
+        for template in &["{a", "a}", "{a{b}}", "{}", "{ }", "}{"] {
+            assert_eq!(parse(template).err(), Some(Error::TemplateNotValid), "{}", template);
+        }
+    }
+
+    #[test]
+    fn eval_maps_throw_and_terminate_to_errors() {
+        let runtime = Runtime::new();
+        let (script, body) = call_script(&runtime, &["1"]);
+        let recorder = Recorder::new();
+        for code in &["a", "a.b.c", "g()"] {
+            body.set_breakpoint(0, Rc::new(Box::new(Evaluator(code, recorder.clone())))).unwrap();
+        }
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec![
+            "Ok(Number(1.0))",
+            "Err(Throw(String(\"TypeError: cannot read property c of undefined\")))",
+            "Err(Terminate)"
+        ]);
+    }
+
+    fn setup() -> (Runtime, Object<Reference>, Debugger<Reference>) {
+        let runtime = Runtime::new();
+        let global = runtime.new_global();
+        let debugger = runtime.new_debugger();
+        debugger.add_debuggee(&global);
+        (runtime, global, debugger)
+    }
+
+    fn new_script(runtime: &Runtime, global: &Object<Reference>) -> Script<Reference> {
+        let source = runtime.new_source(SourceBuilder::new("test.js", ""));
+        runtime.new_script(global, &source, ScriptBuilder::new(1)
+            .op(1, Op::Var("x".to_owned(), "1".to_owned()))
+            .op(2, Op::Var("y".to_owned(), "x + 1".to_owned())))
+    }
+
+    fn is_installed(debugger: &Debugger<Reference>, handler: &Rc<Box<dyn NewScriptHandler<Reference>>>) -> bool {
+        debugger.new_script_handler().map_or(false, |installed| Rc::ptr_eq(&installed, handler))
+    }
+
+    #[test]
+    fn set_breakpoint_binds_to_loaded_and_later_scripts() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let registry = Registry::new(&debugger, Rc::new(Box::new(recorder.clone())));
+        let script = new_script(&runtime, &global);
+        let id = registry.set_breakpoint("test.js", 2, None, Rc::new(Box::new(recorder.clone()))).unwrap();
+        assert_eq!(registry.set_breakpoint("other.js", 2, None, Rc::new(Box::new(recorder.clone()))),
+                   Some(PendingBreakpointId(1)));
+        let later = new_script(&runtime, &global);
+        runtime.execute(&later);
+        assert_eq!(recorder.log(), vec!["bind 0 at 1", "bind 0 at 1", "breakpoint at 1"]);
+        let bindings = registry.get_bindings(id).unwrap();
+        assert_eq!(bindings.iter().map(|(script, _)| script.clone()).collect::<Vec<_>>(), vec![script, later]);
+    }
+
+    #[test]
+    fn reload_drops_bindings_to_scripts_that_are_no_longer_debuggees() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let registry = Registry::new(&debugger, Rc::new(Box::new(recorder.clone())));
+        let old = new_script(&runtime, &global);
+        let id = registry.set_breakpoint("test.js", 2, None, Rc::new(Box::new(recorder.clone()))).unwrap();
+        debugger.remove_debuggee(&global);
+        let reloaded = runtime.new_global();
+        debugger.add_debuggee(&reloaded);
+        let new = new_script(&runtime, &reloaded);
+        let bindings = registry.get_bindings(id).unwrap();
+        assert_eq!(bindings.iter().map(|(script, _)| script.clone()).collect::<Vec<_>>(), vec![new]);
+        assert!(old.get_breakpoints(1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn close_restores_the_new_script_handler() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let previous: Rc<Box<dyn NewScriptHandler<Reference>>> = Rc::new(Box::new(recorder.clone()));
+        debugger.set_new_script_handler(Some(previous.clone()));
+        let registry = Registry::new(&debugger, Rc::new(Box::new(recorder.clone())));
+        let id = registry.set_breakpoint("test.js", 2, None, Rc::new(Box::new(recorder.clone()))).unwrap();
+        let script = new_script(&runtime, &global);
+        registry.close();
+        assert!(is_installed(&debugger, &previous));
+        assert!(!registry.is_open());
+        assert!(registry.get_bindings(id).is_err());
+        assert!(script.get_breakpoints(1).unwrap().is_empty());
+        assert_eq!(registry.set_breakpoint("test.js", 2, None, Rc::new(Box::new(recorder.clone()))), None);
+        assert_eq!(recorder.log(), vec!["bind 0 at 1", "new script test.js"]);
+    }
+
+    #[test]
+    fn close_keeps_a_new_script_handler_installed_in_the_meantime() {
+        let (_runtime, _global, debugger) = setup();
+        let recorder = Recorder::new();
+        let registry = Registry::new(&debugger, Rc::new(Box::new(recorder.clone())));
+        let replacement: Rc<Box<dyn NewScriptHandler<Reference>>> = Rc::new(Box::new(recorder.clone()));
+        debugger.set_new_script_handler(Some(replacement.clone()));
+        registry.close();
+        assert!(is_installed(&debugger, &replacement));
+    }
+
+    #[test]
+    fn drop_closes_the_registry() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let script = new_script(&runtime, &global);
+        {
+            let registry = Registry::new(&debugger, Rc::new(Box::new(recorder.clone())));
+            registry.set_breakpoint("test.js", 2, None, Rc::new(Box::new(recorder.clone()))).unwrap();
+        }
+        assert!(debugger.new_script_handler().is_none());
+        assert!(script.get_breakpoints(1).unwrap().is_empty());
+    }
+}
@@ -1,17 +1,98 @@
+// `Option::is_some_and`, `Option::is_none_or` and `u32::is_multiple_of` are
+// too recent for the toolchains this crate supports.
+#![allow(clippy::manual_is_multiple_of, clippy::unnecessary_map_or)]
+
 use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
 use std::rc::Rc;
 
+mod backend;
+pub mod breakpoint;
+pub mod reference;
+pub mod stepping;
+
+pub use backend::Backend;
+
+/// Defines a wrapper type that owns a handle of the backend type with the same
+/// name.
+macro_rules! wrapper {
+    ($(#[$attribute:meta])* pub struct $name:ident;) => {
+        $(#[$attribute])*
+        pub struct $name<B: Backend> {
+            handle: B::$name
+        }
+
+        impl<B: Backend> $name<B> {
+            /// Returns a wrapper for the given `handle`. This is intended to be
+            /// used by backends.
+            pub fn from_handle(handle: B::$name) -> $name<B> {
+                $name { handle }
+            }
+
+            /// Returns the handle owned by the wrapper. This is intended to be
+            /// used by backends.
+            pub fn handle(&self) -> &B::$name {
+                &self.handle
+            }
+        }
+
+        impl<B: Backend> Clone for $name<B> {
+            fn clone(&self) -> $name<B> {
+                $name { handle: self.handle.clone() }
+            }
+        }
+
+        impl<B: Backend> PartialEq for $name<B> {
+            fn eq(&self, other: &$name<B>) -> bool {
+                self.handle == other.handle
+            }
+        }
+
+        impl<B: Backend> Eq for $name<B> {}
+
+        impl<B: Backend> fmt::Debug for $name<B> {
+            fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.debug_tuple(stringify!($name)).field(&self.handle).finish()
+            }
+        }
+    }
+}
+
 /// An enum describing why a method failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Error {
+    /// The method failed because there is no such breakpoint.
+    BreakpointNotFound,
+
+    /// The method failed because the array buffer is detached.
+    BufferDetached,
+
     /// The method failed because it would cause the debuggee to run.
     DebuggeeWouldRun,
 
+    /// The method failed because the property descriptor is not valid.
+    DescriptorNotValid,
+
     /// The method failed because the environment is not a debuggee environment.
     EnvironmentNotDebuggee,
 
+    /// The method failed because there is no such private field.
+    FieldNotFound,
+
     /// The method failed because the frame is not a debuggee frame.
     FrameNotDebuggee,
 
+    /// The method failed because the line has no line entry points.
+    LineNotValid,
+
+    /// The method failed because the object is not an array buffer or a view
+    /// on one.
+    ObjectNotBinaryData,
+
+    /// The method failed because the object is not callable.
+    ObjectNotCallable,
+
     /// The method failed because the object is not extensible.
     ObjectNotExtensible,
 
@@ -24,6 +105,15 @@ pub enum Error {
     /// The method failed because the property is not configurable.
     PropertyNotConfigurable,
 
+    /// The method failed because the query is not valid.
+    QueryNotValid,
+
+    /// The method failed because the range is not valid.
+    RangeNotValid,
+
+    /// The method failed because the template is not valid.
+    TemplateNotValid,
+
     /// The method failed because there is no such variable.
     VariableNotFound
 }
@@ -31,34 +121,527 @@ pub enum Error {
 /// A return type for methods that are fallible.
 pub type Fallible<T> = Result<T, Error>;
 
+/// An arbitrary-precision integer, as held by a BigInt value in the debuggee.
+///
+/// A BigInt consists of a sign, and a magnitude given as a sequence of base
+/// 2^32 digits, least significant first. The magnitude never has leading zero
+/// digits, and zero is never negative, so two BigInts are equal if and only if
+/// they represent the same integer.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>
+}
+
+impl BigInt {
+    /// Returns a BigInt with the given sign and the magnitude given by the
+    /// given base 2^32 `digits`, least significant first.
+    pub fn new(negative: bool, mut digits: Vec<u32>) -> BigInt {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        BigInt {
+            negative: negative && !digits.is_empty(),
+            digits
+        }
+    }
+
+    /// Returns `true` if the BigInt is negative. Returns `false` otherwise.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the base 2^32 digits of the magnitude of the BigInt, least
+    /// significant first. Zero has no digits.
+    pub fn digits(&self) -> &[u32] {
+        &self.digits
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> BigInt {
+        let magnitude = value.unsigned_abs();
+        BigInt::new(value < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+impl fmt::Display for BigInt {
+    /// Formats the BigInt in decimal, without the `n` suffix.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut magnitude = self.digits.clone();
+        let mut chunks = Vec::new();
+        while !magnitude.is_empty() {
+            let mut remainder = 0u64;
+            for digit in magnitude.iter_mut().rev() {
+                let value = (remainder << 32) | u64::from(*digit);
+                *digit = (value / 1_000_000_000) as u32;
+                remainder = value % 1_000_000_000;
+            }
+            while magnitude.last() == Some(&0) {
+                magnitude.pop();
+            }
+            chunks.push(remainder);
+        }
+        let mut string = if self.negative { "-".to_owned() } else { String::new() };
+        match chunks.pop() {
+            Some(chunk) => string.push_str(&chunk.to_string()),
+            None => string.push('0')
+        }
+        for chunk in chunks.iter().rev() {
+            string.push_str(&format!("{:09}", chunk));
+        }
+        formatter.pad(&string)
+    }
+}
+
 /// A value in the debuggee. This is either a primitive value or a wrapper to an
 /// object in the debuggee. A primitive value is either undefined, null, a
-/// boolean, a string, or a number.
-pub enum Value {
+/// boolean, a string, a number, a BigInt, or a wrapper to a symbol.
+pub enum Value<B: Backend> {
     Undefined,
     Null,
     Boolean(bool),
     String(String),
     Number(f64),
-    Object(Object)
+    BigInt(BigInt),
+    Symbol(Symbol<B>),
+    Object(Object<B>)
+}
+
+impl<B: Backend> Value<B> {
+    /// Returns `true` if the value is truthy; that is, if it converts to
+    /// `true` in JavaScript. Returns `false` otherwise.
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Undefined | Value::Null => false,
+            Value::Boolean(value) => *value,
+            Value::String(value) => !value.is_empty(),
+            Value::Number(value) => *value != 0.0 && !value.is_nan(),
+            Value::BigInt(value) => !value.digits().is_empty(),
+            Value::Symbol(_) | Value::Object(_) => true
+        }
+    }
+}
+
+impl<B: Backend> Clone for Value<B> {
+    fn clone(&self) -> Value<B> {
+        match self {
+            Value::Undefined => Value::Undefined,
+            Value::Null => Value::Null,
+            Value::Boolean(value) => Value::Boolean(*value),
+            Value::String(value) => Value::String(value.clone()),
+            Value::Number(value) => Value::Number(*value),
+            Value::BigInt(value) => Value::BigInt(value.clone()),
+            Value::Symbol(value) => Value::Symbol(value.clone()),
+            Value::Object(value) => Value::Object(value.clone())
+        }
+    }
+}
+
+impl<B: Backend> PartialEq for Value<B> {
+    fn eq(&self, other: &Value<B>) -> bool {
+        match (self, other) {
+            (Value::Undefined, Value::Undefined) => true,
+            (Value::Null, Value::Null) => true,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            (Value::Symbol(a), Value::Symbol(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => a == b,
+            _ => false
+        }
+    }
+}
+
+impl<B: Backend> fmt::Debug for Value<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Undefined => formatter.write_str("Undefined"),
+            Value::Null => formatter.write_str("Null"),
+            Value::Boolean(value) => formatter.debug_tuple("Boolean").field(value).finish(),
+            Value::String(value) => formatter.debug_tuple("String").field(value).finish(),
+            Value::Number(value) => formatter.debug_tuple("Number").field(value).finish(),
+            Value::BigInt(value) => formatter.debug_tuple("BigInt").field(value).finish(),
+            Value::Symbol(value) => formatter.debug_tuple("Symbol").field(value).finish(),
+            Value::Object(value) => formatter.debug_tuple("Object").field(value).finish()
+        }
+    }
+}
+
+impl<B: Backend> fmt::Display for Value<B> {
+    /// Formats the value as `String(value)` does in JavaScript, except that
+    /// no debuggee code is run: errors are formatted as their name and
+    /// message, and other objects as `[object Class]`.
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Undefined => formatter.pad("undefined"),
+            Value::Null => formatter.pad("null"),
+            Value::Boolean(value) => formatter.pad(if *value { "true" } else { "false" }),
+            Value::String(value) => formatter.pad(value),
+            Value::Number(value) => formatter.pad(&number_to_string(*value)),
+            Value::BigInt(value) => value.fmt(formatter),
+            Value::Symbol(symbol) => {
+                formatter.pad(&format!("Symbol({})", symbol.description().unwrap_or_default()))
+            },
+            Value::Object(object) => match (object.error_name(), object.error_message()) {
+                (Some(name), Some(ref message)) if !message.is_empty() => {
+                    formatter.pad(&format!("{}: {}", name, message))
+                },
+                (Some(name), _) => formatter.pad(&name),
+                _ => formatter.pad(&format!("[object {}]", object.class()))
+            }
+        }
+    }
+}
+
+/// Converts the given number to a string, following the rules of
+/// `Number.prototype.toString` in JavaScript: the shortest digits that round
+/// trip, in exponential notation for exponents below -6 or above 20.
+fn number_to_string(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_owned();
+    }
+    if value == 0.0 {
+        return "0".to_owned();
+    }
+    if value < 0.0 {
+        return format!("-{}", number_to_string(-value));
+    }
+    if value.is_infinite() {
+        return "Infinity".to_owned();
+    }
+    // Rust formats the shortest digits that round trip as `d.ddde±x`.
+    let scientific = format!("{:e}", value);
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap());
+    let digits = mantissa.replace('.', "");
+    let length = digits.len() as i32;
+    let point = exponent[1..].parse::<i32>().unwrap() + 1;
+    if length <= point && point <= 21 {
+        digits + &"0".repeat((point - length) as usize)
+    } else if 0 < point && point <= 21 {
+        format!("{}.{}", &digits[..point as usize], &digits[point as usize..])
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{}", "0".repeat(-point as usize), digits)
+    } else {
+        let sign = if point > 0 { '+' } else { '-' };
+        match digits.split_at(1) {
+            (first, "") => format!("{}e{}{}", first, sign, (point - 1).abs()),
+            (first, rest) => format!("{}.{}e{}{}", first, rest, sign, (point - 1).abs())
+        }
+    }
 }
 
 /// A completion value describes how a call or evaluation completed.
-pub enum CompletionValue {
+pub enum CompletionValue<B: Backend> {
     /// The call or evaluation returned the given value as a result.
-    Return(Value),
+    Return(Value<B>),
 
     /// The call or evaluation threw the given value as an exception.
-    Throw(Value),
+    Throw(Value<B>),
 
     /// The call or evaluation was terminated.
-    Terminate
+    Terminate,
+
+    /// The frame of a generator was suspended by yielding the given value.
+    /// This completion value is only passed to pop handlers. Returning it as
+    /// a resumption value is equivalent to returning `Return` with the given
+    /// value.
+    Yield(Value<B>),
+
+    /// The frame of an async function was suspended by awaiting the given
+    /// value. This completion value is only passed to pop handlers. Returning
+    /// it as a resumption value is equivalent to returning `Return` with the
+    /// given value.
+    Await(Value<B>)
+}
+
+impl<B: Backend> Clone for CompletionValue<B> {
+    fn clone(&self) -> CompletionValue<B> {
+        match self {
+            CompletionValue::Return(value) => CompletionValue::Return(value.clone()),
+            CompletionValue::Throw(value) => CompletionValue::Throw(value.clone()),
+            CompletionValue::Terminate => CompletionValue::Terminate,
+            CompletionValue::Yield(value) => CompletionValue::Yield(value.clone()),
+            CompletionValue::Await(value) => CompletionValue::Await(value.clone())
+        }
+    }
+}
+
+impl<B: Backend> PartialEq for CompletionValue<B> {
+    fn eq(&self, other: &CompletionValue<B>) -> bool {
+        match (self, other) {
+            (CompletionValue::Return(a), CompletionValue::Return(b)) => a == b,
+            (CompletionValue::Throw(a), CompletionValue::Throw(b)) => a == b,
+            (CompletionValue::Terminate, CompletionValue::Terminate) => true,
+            (CompletionValue::Yield(a), CompletionValue::Yield(b)) => a == b,
+            (CompletionValue::Await(a), CompletionValue::Await(b)) => a == b,
+            _ => false
+        }
+    }
+}
+
+impl<B: Backend> fmt::Debug for CompletionValue<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompletionValue::Return(value) => formatter.debug_tuple("Return").field(value).finish(),
+            CompletionValue::Throw(value) => formatter.debug_tuple("Throw").field(value).finish(),
+            CompletionValue::Terminate => formatter.write_str("Terminate"),
+            CompletionValue::Yield(value) => formatter.debug_tuple("Yield").field(value).finish(),
+            CompletionValue::Await(value) => formatter.debug_tuple("Await").field(value).finish()
+        }
+    }
 }
 
 /// A resumption value describes how the debuggee should continue executing.
-pub type ResumptionValue = Option<CompletionValue>;
+pub type ResumptionValue<B> = Option<CompletionValue<B>>;
+
+/// A trait for values that can be used as debugger statement handler.
+pub trait DebuggerStatementHandler<B: Backend> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B>;
+}
+
+/// A trait for values that can be used as enter frame handler.
+pub trait EnterFrameHandler<B: Backend> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B>;
+}
+
+/// A trait for values that can be used as exception unwind handler.
+pub trait ExceptionUnwindHandler<B: Backend> {
+    fn handle(&self, frame: &Frame<B>, exception: &Value<B>) -> ResumptionValue<B>;
+}
+
+/// A trait for values that can be used as new script handler.
+pub trait NewScriptHandler<B: Backend> {
+    fn handle(&self, script: &Script<B>);
+}
+
+/// A script query describes which scripts to find. Each attribute that is not
+/// `None` restricts the scripts that are found.
+pub struct ScriptQuery<B: Backend> {
+    /// If not `None`, only scripts loaded from the document with the given url
+    /// are found.
+    pub url: Option<String>,
+
+    /// If not `None`, only scripts whose code spans the given line are found.
+    /// Requires either `url` or `source` to be given.
+    pub line: Option<u32>,
+
+    /// If not `None`, only scripts compiled from the given source are found.
+    pub source: Option<Source<B>>,
+
+    /// If not `None`, only scripts in the global of the given object are
+    /// found.
+    pub global: Option<Object<B>>,
+
+    /// If `true`, only the innermost scripts that span `line` are found; that
+    /// is, scripts that have a child script spanning `line` are skipped.
+    /// Requires `line` to be given.
+    pub innermost: bool
+}
+
+impl<B: Backend> ScriptQuery<B> {
+    /// Returns a query that finds all scripts.
+    pub fn new() -> ScriptQuery<B> {
+        ScriptQuery {
+            url: None,
+            line: None,
+            source: None,
+            global: None,
+            innermost: false
+        }
+    }
+
+    /// Returns `true` if the query is valid. Returns `false` otherwise.
+    pub fn is_valid(&self) -> bool {
+        (self.line.is_none() || self.url.is_some() || self.source.is_some()) &&
+        (!self.innermost || self.line.is_some())
+    }
+}
+
+impl<B: Backend> Default for ScriptQuery<B> {
+    fn default() -> ScriptQuery<B> {
+        ScriptQuery::new()
+    }
+}
+
+/// An object query describes which objects to find. Each attribute that is not
+/// `None` restricts the objects that are found.
+pub struct ObjectQuery<B: Backend> {
+    /// If not `None`, only objects whose class, as returned by
+    /// `Object::class`, is the given class are found.
+    pub class: Option<String>,
+
+    /// If not `None`, only objects in the global of the given object are
+    /// found.
+    pub global: Option<Object<B>>
+}
+
+impl<B: Backend> ObjectQuery<B> {
+    /// Returns a query that finds all objects.
+    pub fn new() -> ObjectQuery<B> {
+        ObjectQuery {
+            class: None,
+            global: None
+        }
+    }
+}
+
+impl<B: Backend> Default for ObjectQuery<B> {
+    fn default() -> ObjectQuery<B> {
+        ObjectQuery::new()
+    }
+}
+
+wrapper! {
+    /// A debugger. This is the entry point to the API: every other wrapper is
+    /// obtained, directly or indirectly, from a debugger.
+    pub struct Debugger;
+}
+
+impl<B: Backend> Debugger<B> {
+    /// Adds the global of the given `object` as a debuggee of the debugger.
+    /// Returns a wrapper to that global. If the global already is a debuggee,
+    /// this method has no effect.
+    pub fn add_debuggee(&self, object: &Object<B>) -> Object<B> {
+        B::debugger_add_debuggee(&self.handle, object)
+    }
+
+    /// Returns the debugger statement handler for the debugger. If there is no
+    /// debugger statement handler for the debugger, returns `None` instead.
+    pub fn debugger_statement_handler(&self) -> Option<Rc<Box<dyn DebuggerStatementHandler<B>>>> {
+        B::debugger_debugger_statement_handler(&self.handle)
+    }
+
+    /// Returns the enter frame handler for the debugger. If there is no enter
+    /// frame handler for the debugger, returns `None` instead.
+    pub fn enter_frame_handler(&self) -> Option<Rc<Box<dyn EnterFrameHandler<B>>>> {
+        B::debugger_enter_frame_handler(&self.handle)
+    }
+
+    /// Returns the exception unwind handler for the debugger. If there is no
+    /// exception unwind handler for the debugger, returns `None` instead.
+    pub fn exception_unwind_handler(&self) -> Option<Rc<Box<dyn ExceptionUnwindHandler<B>>>> {
+        B::debugger_exception_unwind_handler(&self.handle)
+    }
+
+    /// Returns wrappers to the objects in the debuggees of the debugger that
+    /// match the given `query`.
+    pub fn find_objects(&self, query: &ObjectQuery<B>) -> Vec<Object<B>> {
+        B::debugger_find_objects(&self.handle, query)
+    }
+
+    /// Returns wrappers to the scripts in the debuggees of the debugger that
+    /// match the given `query`. This includes the child scripts of top-level
+    /// scripts.
+    ///
+    /// # Errors
+    /// If the given `query` is not valid, returns `QueryNotValid`.
+    pub fn find_scripts(&self, query: &ScriptQuery<B>) -> Fallible<Vec<Script<B>>> {
+        if !query.is_valid() {
+            return Err(Error::QueryNotValid);
+        }
+        Ok(B::debugger_find_scripts(&self.handle, query))
+    }
+
+    /// Returns wrappers to the sources of the scripts in the debuggees of the
+    /// debugger.
+    pub fn find_sources(&self) -> Vec<Source<B>> {
+        B::debugger_find_sources(&self.handle)
+    }
+
+    /// Returns the breakpoints in the scripts in the debuggees of the debugger,
+    /// ordered by identifier.
+    pub fn get_all_breakpoints(&self) -> Vec<Breakpoint<B>> {
+        B::debugger_get_all_breakpoints(&self.handle)
+    }
+
+    /// Returns wrappers to the debuggees of the debugger.
+    pub fn get_debuggees(&self) -> Vec<Object<B>> {
+        B::debugger_get_debuggees(&self.handle)
+    }
+
+    /// Returns a wrapper to the youngest visible frame on the stack. If there
+    /// is no such frame, returns `None` instead.
+    pub fn get_newest_frame(&self) -> Option<Frame<B>> {
+        B::debugger_get_newest_frame(&self.handle)
+    }
+
+    /// Returns `true` if the global of the given `object` is a debuggee of the
+    /// debugger. Returns `false` otherwise.
+    pub fn has_debuggee(&self, object: &Object<B>) -> bool {
+        B::debugger_has_debuggee(&self.handle, object)
+    }
+
+    /// Returns the memory interface of the debugger.
+    pub fn memory(&self) -> Memory<B> {
+        Memory { debugger: self.handle.clone() }
+    }
+
+    /// Returns `true` if the debugger is enabled. Returns `false` otherwise.
+    /// A disabled debugger does not call any of its handlers, or the handlers
+    /// of the frames and scripts obtained from it.
+    pub fn is_enabled(&self) -> bool {
+        B::debugger_is_enabled(&self.handle)
+    }
+
+    /// Returns the new script handler for the debugger. If there is no new
+    /// script handler for the debugger, returns `None` instead.
+    pub fn new_script_handler(&self) -> Option<Rc<Box<dyn NewScriptHandler<B>>>> {
+        B::debugger_new_script_handler(&self.handle)
+    }
+
+    /// Removes the global of the given `object` as a debuggee of the debugger.
+    /// If the global is not a debuggee, this method has no effect.
+    pub fn remove_debuggee(&self, object: &Object<B>) {
+        B::debugger_remove_debuggee(&self.handle, object)
+    }
+
+    /// Sets the debugger statement handler for the debugger to the given
+    /// `handler`. When a debuggee executes a `debugger` statement, the `handle`
+    /// method of the given `handler` will be called. If the given `handler` is
+    /// `None`, the debugger statement handler for the debugger is cleared
+    /// instead.
+    pub fn set_debugger_statement_handler(&self, handler: Option<Rc<Box<dyn DebuggerStatementHandler<B>>>>) {
+        B::debugger_set_debugger_statement_handler(&self.handle, handler)
+    }
+
+    /// Enables the debugger if `enabled` is `true`. Disables it otherwise.
+    pub fn set_enabled(&self, enabled: bool) {
+        B::debugger_set_enabled(&self.handle, enabled)
+    }
+
+    /// Sets the enter frame handler for the debugger to the given `handler`.
+    /// When a new frame is pushed on the stack of a debuggee, before it
+    /// executes any code, the `handle` method of the given `handler` will be
+    /// called. This includes the frames of generators and async functions that
+    /// are pushed again when they are resumed. If the given `handler` is
+    /// `None`, the enter frame handler for the debugger is cleared instead.
+    pub fn set_enter_frame_handler(&self, handler: Option<Rc<Box<dyn EnterFrameHandler<B>>>>) {
+        B::debugger_set_enter_frame_handler(&self.handle, handler)
+    }
+
+    /// Sets the exception unwind handler for the debugger to the given
+    /// `handler`. When an exception is thrown in a frame of a debuggee, or
+    /// propagates into it from a frame it called, the `handle` method of the
+    /// given `handler` will be called with that frame and the exception. If the
+    /// given `handler` is `None`, the exception unwind handler for the debugger
+    /// is cleared instead.
+    pub fn set_exception_unwind_handler(&self, handler: Option<Rc<Box<dyn ExceptionUnwindHandler<B>>>>) {
+        B::debugger_set_exception_unwind_handler(&self.handle, handler)
+    }
+
+    /// Sets the new script handler for the debugger to the given `handler`.
+    /// When a new top-level script is compiled in a debuggee, the `handle`
+    /// method of the given `handler` will be called. If the given `handler` is
+    /// `None`, the new script handler for the debugger is cleared instead.
+    pub fn set_new_script_handler(&self, handler: Option<Rc<Box<dyn NewScriptHandler<B>>>>) {
+        B::debugger_set_new_script_handler(&self.handle, handler)
+    }
+}
 
 /// An enum describing the type of an environment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum EnvironmentType {
     /// And environment introduced by a function call, call to `eval`, etc.
     Declarative,
@@ -70,10 +653,12 @@ pub enum EnvironmentType {
     With
 }
 
-/// A wrapper to a lexical environment 
-pub struct Environment;
+wrapper! {
+    /// A wrapper to a lexical environment
+    pub struct Environment;
+}
 
-impl Environment {
+impl<B: Backend> Environment<B> {
     /// If the wrapped environment is the variable environment for a function
     /// call, returns a wrapper to the function being called. Otherwise,
     /// returns `None`.
@@ -81,8 +666,8 @@ impl Environment {
     /// # Errors
     /// If the wrapped environment could not be inspected because it is not a
     /// debuggee environment, returns `EnvironmentNotDebuggee`.
-    pub fn callee(&self) -> Fallible<Option<Object>> {
-        unimplemented!()
+    pub fn callee(&self) -> Fallible<Option<Object<B>>> {
+        B::environment_callee(&self.handle)
     }
 
     /// Returns a wrapper to the innermost environment that binds a variable to
@@ -96,8 +681,8 @@ impl Environment {
     ///
     /// If a wrapped environment could not be inspected because it is not a
     /// debuggee environment, returns `EnvironmentNotDebuggee`.
-    pub fn find(&self, name: &str) -> Fallible<Option<Environment>> {
-        unimplemented!()
+    pub fn find(&self, name: &str) -> Fallible<Option<Environment<B>>> {
+        B::environment_find(&self.handle, name)
     }
 
     /// Returns the value of the variable bound to the given `name` by the
@@ -111,14 +696,14 @@ impl Environment {
     ///
     /// If the wrapped environment could not be inspected because it is not a
     /// debuggee environment, returns `EnvironmentNotDebuggee`.
-    pub fn get_variable(&self, name: &str) -> Fallible<Value> {
-        unimplemented!()
+    pub fn get_variable(&self, name: &str) -> Fallible<Value<B>> {
+        B::environment_get_variable(&self.handle, name)
     }
 
     /// Returns `true` if the wrapped environment is a debuggee environment.
     /// Returns `false` otherwise.
-    pub fn is_inspectable() -> bool {
-        unimplemented!()
+    pub fn is_inspectable(&self) -> bool {
+        B::environment_is_inspectable(&self.handle)
     }
 
     /// Returns the names of the variables bound by the wrapped environment.
@@ -131,7 +716,7 @@ impl Environment {
     /// If the wrapped environment could not be inspected because it is not a
     /// debuggee environment, returns `EnvironmentNotDebuggee`.
     pub fn names(&self) -> Fallible<Vec<String>> {
-        unimplemented!()
+        B::environment_names(&self.handle)
     }
 
     /// If the wrapped environment is a global or with environment, returns a
@@ -141,8 +726,8 @@ impl Environment {
     /// # Errors
     /// If the wrapped environment could not be inspected because it is not a
     /// debuggee environment, returns `EnvironmentNotDebuggee`.
-    pub fn object(&self) -> Fallible<Option<Object>> {
-        unimplemented!()
+    pub fn object(&self) -> Fallible<Option<Object<B>>> {
+        B::environment_object(&self.handle)
     }
 
     /// Returns `true` if the wrapped environment is optimized out. Returns
@@ -152,7 +737,7 @@ impl Environment {
     /// If the wrapped environment could not be inspected because it is not a
     /// debuggee environment, returns `EnvironmentNotDebuggee`.
     pub fn is_optimized_out(&self) -> Fallible<bool> {
-        unimplemented!()
+        B::environment_is_optimized_out(&self.handle)
     }
 
     /// Returns a wrapper to the environment enclosing the wrapped environment.
@@ -163,8 +748,8 @@ impl Environment {
     /// # Errors
     /// If the wrapped environment could not be inspected because it is not a
     /// debuggee environment, returns `EnvironmentNotDebuggee`.
-    pub fn parent(&self) -> Fallible<Option<Environment>> {
-        unimplemented!()
+    pub fn parent(&self) -> Fallible<Option<Environment<B>>> {
+        B::environment_parent(&self.handle)
     }
 
     /// Sets the value of the variable bound to the given `name` by the wrapped
@@ -180,8 +765,8 @@ impl Environment {
     ///
     /// If the value of the variable could not be set because there is no such
     /// variable, returns `VariableNotFound`.
-    pub fn set_variable(&self, name: &str, value: &Value) -> Fallible<()> {
-        unimplemented!()
+    pub fn set_variable(&self, name: &str, value: &Value<B>) -> Fallible<()> {
+        B::environment_set_variable(&self.handle, name, value)
     }
 
     /// Returns the type of the wrapped environment.
@@ -190,11 +775,52 @@ impl Environment {
     /// If the wrapped environment could not be inspected because it is not a
     /// debuggee environment, returns `EnvironmentNotDebuggee`.
     pub fn get_type(&self) -> Fallible<EnvironmentType> {
-        unimplemented!()
+        B::environment_get_type(&self.handle)
+    }
+}
+
+/// A closure scope describes an environment enclosing a function, along with
+/// the variables it binds.
+pub struct ClosureScope<B: Backend> {
+    /// A wrapper to the environment.
+    pub environment: Environment<B>,
+
+    /// The type of the environment.
+    pub environment_type: EnvironmentType,
+
+    /// Whether the environment is optimized out.
+    pub optimized_out: bool,
+
+    /// The names of the variables bound by the environment, along with their
+    /// values. If the environment is optimized out, the values are not
+    /// available, and are `None` instead.
+    pub bindings: Vec<(String, Option<Value<B>>)>
+}
+
+impl<B: Backend> Clone for ClosureScope<B> {
+    fn clone(&self) -> ClosureScope<B> {
+        ClosureScope {
+            environment: self.environment.clone(),
+            environment_type: self.environment_type,
+            optimized_out: self.optimized_out,
+            bindings: self.bindings.clone()
+        }
+    }
+}
+
+impl<B: Backend> fmt::Debug for ClosureScope<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("ClosureScope")
+                 .field("environment", &self.environment)
+                 .field("environment_type", &self.environment_type)
+                 .field("optimized_out", &self.optimized_out)
+                 .field("bindings", &self.bindings)
+                 .finish()
     }
 }
 
 /// An enum describing where a frame is executing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FrameImplementation {
     /// A frame executing in the interpreter.
     Interpreter,
@@ -207,6 +833,7 @@ pub enum FrameImplementation {
 }
 
 /// An enum describing the type of a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FrameType {
     /// A frame introduced by a function call.
     Call,
@@ -222,47 +849,49 @@ pub enum FrameType {
 }
 
 /// A trait for values that can be used as pop handler.
-pub trait PopHandler {
-    fn handle(&self, frame: &Frame, completion: &CompletionValue) -> ResumptionValue;
+pub trait PopHandler<B: Backend> {
+    fn handle(&self, frame: &Frame<B>, completion: &CompletionValue<B>) -> ResumptionValue<B>;
 }
 
 /// A trait for values that can be used as step handler.
-pub trait StepHandler {
-    fn handle(&self, frame: &Frame) -> ResumptionValue;
+pub trait StepHandler<B: Backend> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B>;
 }
 
-/// A wrapper to a stack frame.
-pub struct Frame;
+wrapper! {
+    /// A wrapper to a stack frame.
+    pub struct Frame;
+}
 
-impl Frame {
+impl<B: Backend> Frame<B> {
     /// If the wrapped frame is a call frame, returns the arguments for the
     /// call. Otherwise, returns `None`.
-    pub fn arguments(&self) -> Option<Vec<Value>> {
-        unimplemented!()
+    pub fn arguments(&self) -> Option<Vec<Value<B>>> {
+        B::frame_arguments(&self.handle)
     }
 
     /// If the wrapped frame is a call frame, returns a wrapper to the function
     /// being called. Otherwise, returns `None`.
-    pub fn callee(&self) -> Option<Object> {
-        unimplemented!()
+    pub fn callee(&self) -> Option<Object<B>> {
+        B::frame_callee(&self.handle)
     }
 
     /// Returns the depth of the wrapped frame on the stack.
     pub fn depth(&self) -> u32 {
-        unimplemented!()
+        B::frame_depth(&self.handle)
     }
 
     /// Returns `true` if the wrapped frame is a call frame for a function being
     /// called as a constructor. Returns `false` otherwise.
     pub fn is_constructing(&self) -> bool {
-        unimplemented!()
+        B::frame_is_constructing(&self.handle)
     }
 
     /// Returns the environment in which the wrapped frame is executing. If
     /// the wrapped frame does not have an environment because it is not a
     /// debuggee frame, returns `None` instead.
-    pub fn environment(&self) -> Option<Environment> {
-        unimplemented!()
+    pub fn environment(&self) -> Option<Environment<B>> {
+        B::frame_environment(&self.handle)
     }
 
     /// Evaluates the given `code` in the environment of the wrapped frame.
@@ -271,8 +900,8 @@ impl Frame {
     /// # Errors
     /// If wrapped frame does not have an environment because it is not a
     /// debuggee frame, returns `FrameNotDebuggee`.
-    pub fn eval(&self, code: &str) -> CompletionValue {
-        unimplemented!()
+    pub fn eval(&self, code: &str) -> Fallible<CompletionValue<B>> {
+        B::frame_eval(&self.handle, code)
     }
 
     /// Evaluates the given `code` in the environment of the wrapped frame,
@@ -283,64 +912,78 @@ impl Frame {
     /// # Errors
     /// If wrapped frame does not have an environment because it is not a
     /// debuggee frame, returns `FrameNotDebuggee`.
-    pub fn eval_with_bindings(&self, code: &str, bindings: &BTreeMap<String, Value>) -> CompletionValue {
-        unimplemented!()
+    pub fn eval_with_bindings(&self, code: &str, bindings: &BTreeMap<String, Value<B>>) -> Fallible<CompletionValue<B>> {
+        B::frame_eval_with_bindings(&self.handle, code, bindings)
     }
 
     /// Returns the type of the wrapped frame.
     pub fn get_type(&self) -> FrameType {
-        unimplemented!()
+        B::frame_get_type(&self.handle)
     }
 
     /// Returns the implementation of the wrapped frame.
     pub fn implementation(&self) -> FrameImplementation {
-        unimplemented!()
+        B::frame_implementation(&self.handle)
     }
 
     /// Returns `true` if the wrapped frame is still on the stack. Returns
-    /// `false` otherwise.
+    /// `false` otherwise. A suspended frame is not on the stack.
     pub fn is_live(&self) -> bool {
-        unimplemented!()
+        B::frame_is_live(&self.handle)
+    }
+
+    /// Returns `true` if the wrapped frame is the frame of a generator or async
+    /// function that is suspended; that is, either suspended at a `yield` or
+    /// `await`, or, for a generator, not yet started. Returns `false`
+    /// otherwise.
+    ///
+    /// When a suspended frame is resumed, it is pushed on the stack again as
+    /// the same frame: wrappers obtained before it was suspended compare equal
+    /// to those obtained after it was resumed, and its handlers are kept.
+    pub fn is_suspended(&self) -> bool {
+        B::frame_is_suspended(&self.handle)
     }
 
     /// Returns the offset of the bytecode being executed in the script of the
     /// wrapped frame. If the wrapped frame does not have a script because it is
     /// not a debuggee frame, returns `None` instead.
     pub fn offset(&self) -> Option<u32> {
-        unimplemented!()
+        B::frame_offset(&self.handle)
     }
 
     /// Returns a wrapper to the next-older visible frame. If there is no such
     /// frame, returns `None` instead.
-    pub fn older(&self) -> Option<Frame> {
-        unimplemented!()
+    pub fn older(&self) -> Option<Frame<B>> {
+        B::frame_older(&self.handle)
     }
 
     /// Returns the pop handler for the wrapped frame. If there is no pop
     /// handler for the wrapped frame, returns `None` instead.
-    pub fn pop_handler(&self) -> Option<Rc<Box<PopHandler>>> {
-        unimplemented!()
+    pub fn pop_handler(&self) -> Option<Rc<Box<dyn PopHandler<B>>>> {
+        B::frame_pop_handler(&self.handle)
     }
 
     /// Returns the step handler for the wrapped frame. If there is no step
     /// handler for the wrapped frame, returns `None` instead.
-    pub fn step_handler(&self) -> Option<Rc<Box<StepHandler>>> {
-        unimplemented!()
+    pub fn step_handler(&self) -> Option<Rc<Box<dyn StepHandler<B>>>> {
+        B::frame_step_handler(&self.handle)
     }
 
     /// Returns the script being executed in the wrapped frame. If the wrapped
     /// frame does not have a script because it is not a debuggee frame, returns
     /// `None` instead.
-    pub fn script(&self) -> Option<Script> {
-        unimplemented!()
+    pub fn script(&self) -> Option<Script<B>> {
+        B::frame_script(&self.handle)
     }
 
     /// Sets the pop handler for the wrapped frame to the given `handler`. When
     /// the wrapped frame is popped from the stack, the `handle` method of the
-    /// given `handler` will be called. If the given `handler` is `None`, the
-    /// pop handler for the wrapped frame is cleared instead.
-    pub fn set_pop_handler(&self, handler: Option<Rc<Box<PopHandler>>>) -> () {
-        unimplemented!()
+    /// given `handler` will be called. This includes when the frame is popped
+    /// because it is suspended, in which case the completion value is `Yield`
+    /// or `Await`. If the given `handler` is `None`, the pop handler for the
+    /// wrapped frame is cleared instead.
+    pub fn set_pop_handler(&self, handler: Option<Rc<Box<dyn PopHandler<B>>>>) {
+        B::frame_set_pop_handler(&self.handle, handler)
     }
 
     /// Sets the step handler for the wrapped frame to the given `handler`. When
@@ -348,14 +991,180 @@ impl Frame {
     /// frame changes, the `handle` method of the given `handler` will be
     /// called. If the given handler is `None`, the step handler for the wrapped
     /// frame is cleared instead.
-    pub fn set_step_handler(&self, handler: Option<Rc<Box<StepHandler>>>) -> () {
-        unimplemented!()
+    pub fn set_step_handler(&self, handler: Option<Rc<Box<dyn StepHandler<B>>>>) {
+        B::frame_set_step_handler(&self.handle, handler)
     }
 
     /// If the wrapped frame is a call frame, returns the this value for the
-    /// call. Otherwise, returns `None`.
-    pub fn this(&self) -> Value {
-        unimplemented!()
+    /// call. Otherwise, returns the this value of the global code or `eval`
+    /// code being executed.
+    pub fn this(&self) -> Value<B> {
+        B::frame_this(&self.handle)
+    }
+}
+
+/// A saved frame describes a frame that was on the stack at some point in the
+/// past, such as when an object was allocated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SavedFrame {
+    /// The url of the document from which the source of the script of the
+    /// frame was loaded.
+    pub url: String,
+
+    /// The line at which the frame was executing.
+    pub line: u32,
+
+    /// The column at which the frame was executing.
+    pub column: u32,
+
+    /// The name of the function of the frame. If the frame is not a call
+    /// frame, or the function has no name, this is `None` instead.
+    pub function_name: Option<String>
+}
+
+/// A source location describes a position in the document from which the
+/// source of a script was loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// The url of the document.
+    pub url: String,
+
+    /// The line of the position in the document.
+    pub line: u32,
+
+    /// The column of the position in the document.
+    pub column: u32
+}
+
+/// A function location describes the range of the source of a script that is
+/// spanned by the code of a function.
+pub struct FunctionLocation<B: Backend> {
+    /// The source that contains the code of the function.
+    pub source: Source<B>,
+
+    /// The line at which the code of the function starts.
+    pub start_line: u32,
+
+    /// The column at which the code of the function starts.
+    pub start_column: u32,
+
+    /// The line at which the code of the function ends.
+    pub end_line: u32,
+
+    /// The column just past the last character of the code of the function.
+    pub end_column: u32
+}
+
+impl<B: Backend> Clone for FunctionLocation<B> {
+    fn clone(&self) -> FunctionLocation<B> {
+        FunctionLocation {
+            source: self.source.clone(),
+            start_line: self.start_line,
+            start_column: self.start_column,
+            end_line: self.end_line,
+            end_column: self.end_column
+        }
+    }
+}
+
+impl<B: Backend> PartialEq for FunctionLocation<B> {
+    fn eq(&self, other: &FunctionLocation<B>) -> bool {
+        self.source == other.source &&
+        self.start_line == other.start_line &&
+        self.start_column == other.start_column &&
+        self.end_line == other.end_line &&
+        self.end_column == other.end_column
+    }
+}
+
+impl<B: Backend> Eq for FunctionLocation<B> {}
+
+impl<B: Backend> fmt::Debug for FunctionLocation<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("FunctionLocation")
+                 .field("source", &self.source)
+                 .field("start_line", &self.start_line)
+                 .field("start_column", &self.start_column)
+                 .field("end_line", &self.end_line)
+                 .field("end_column", &self.end_column)
+                 .finish()
+    }
+}
+
+/// An entry in the allocations log of a debugger.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllocationLogEntry {
+    /// The time at which the object was allocated. Timestamps never decrease,
+    /// but their unit is up to the backend.
+    pub timestamp: u64,
+
+    /// The class of the object, as returned by `Object::class`.
+    pub class: String,
+
+    /// The number of bytes occupied by the object when it was allocated.
+    pub size: usize,
+
+    /// The frames that were on the stack when the object was allocated,
+    /// youngest first.
+    pub stack: Vec<SavedFrame>
+}
+
+/// The number of objects of a class, and the number of bytes they occupy, as
+/// counted by a census.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClassCensus {
+    /// The number of objects of the class.
+    pub count: usize,
+
+    /// The total number of bytes occupied by the objects of the class.
+    pub bytes: usize
+}
+
+/// The memory interface of a debugger. This provides a view of the heap of the
+/// debuggees of the debugger.
+pub struct Memory<B: Backend> {
+    debugger: B::Debugger
+}
+
+impl<B: Backend> Clone for Memory<B> {
+    fn clone(&self) -> Memory<B> {
+        Memory { debugger: self.debugger.clone() }
+    }
+}
+
+impl<B: Backend> fmt::Debug for Memory<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("Memory").field(&self.debugger).finish()
+    }
+}
+
+impl<B: Backend> Memory<B> {
+    /// Returns the entries in the allocations log of the debugger, oldest
+    /// first, and clears the log.
+    pub fn drain_allocations_log(&self) -> Vec<AllocationLogEntry> {
+        B::memory_drain_allocations_log(&self.debugger)
+    }
+
+    /// Returns `true` if the debugger records allocations in its debuggees.
+    /// Returns `false` otherwise.
+    pub fn is_recording_allocations(&self) -> bool {
+        B::memory_is_recording_allocations(&self.debugger)
+    }
+
+    /// Sets whether the debugger records allocations in its debuggees. While
+    /// allocations are recorded, each object allocated in a debuggee is added
+    /// to the allocations log of the debugger, and the stack at the time of
+    /// its allocation is saved as its allocation site. The log is left as is
+    /// when recording stops.
+    pub fn set_recording_allocations(&self, recording: bool) {
+        B::memory_set_recording_allocations(&self.debugger, recording)
+    }
+
+    /// Returns the number of objects in the debuggees of the debugger, and the
+    /// number of bytes they occupy, broken down by class. Classes are the
+    /// same as those returned by `Object::class`.
+    pub fn take_census(&self) -> BTreeMap<String, ClassCensus> {
+        B::memory_take_census(&self.debugger)
     }
 }
 
@@ -367,7 +1176,7 @@ impl Frame {
 /// descriptor has either a writable or a value attribute. An accessor property
 /// has either a get or a set attribute. A generic property descriptor is
 /// neither a data property descriptor nor an accessor property descriptor.
-pub struct PropertyDescriptor {
+pub struct PropertyDescriptor<B: Backend> {
     /// If `false`, the attributes of the property, except its value, may not
     /// be changed. Defaults to `false`.
     configurable: Option<bool>,
@@ -381,47 +1190,409 @@ pub struct PropertyDescriptor {
     writable: Option<bool>,
 
     /// The value of the property. Defaults to `Undefined`.
-    value: Option<Value>,
+    value: Option<Value<B>>,
 
     /// A getter for the property. Must be either a wrapper to a function or
     /// `Undefined`. Defaults to `Undefined`.
-    get: Option<Value>,
+    get: Option<Value<B>>,
 
     /// A setter for the property. Must be either a wrapper to a function or
     /// `Undefined`. Defaults to `Undefined`.
-    set: Option<Value>
+    set: Option<Value<B>>
+}
+
+impl<B: Backend> PropertyDescriptor<B> {
+    /// Returns a generic property descriptor without any attributes.
+    pub fn generic() -> PropertyDescriptor<B> {
+        PropertyDescriptor {
+            configurable: None,
+            enumerable: None,
+            writable: None,
+            value: None,
+            get: None,
+            set: None
+        }
+    }
+
+    /// Returns a data property descriptor with the given `value`, and no other
+    /// attributes.
+    pub fn data(value: Value<B>) -> PropertyDescriptor<B> {
+        PropertyDescriptor::generic().with_value(value)
+    }
+
+    /// Returns an accessor property descriptor with the given getter and
+    /// setter, and no other attributes. Use `Undefined` for a missing getter
+    /// or setter.
+    pub fn accessor(get: Value<B>, set: Value<B>) -> PropertyDescriptor<B> {
+        PropertyDescriptor::generic().with_get(get).with_set(set)
+    }
+
+    /// Sets the configurable attribute of the property descriptor.
+    pub fn with_configurable(mut self, configurable: bool) -> PropertyDescriptor<B> {
+        self.configurable = Some(configurable);
+        self
+    }
+
+    /// Sets the enumerable attribute of the property descriptor.
+    pub fn with_enumerable(mut self, enumerable: bool) -> PropertyDescriptor<B> {
+        self.enumerable = Some(enumerable);
+        self
+    }
+
+    /// Sets the writable attribute of the property descriptor.
+    pub fn with_writable(mut self, writable: bool) -> PropertyDescriptor<B> {
+        self.writable = Some(writable);
+        self
+    }
+
+    /// Sets the value attribute of the property descriptor.
+    pub fn with_value(mut self, value: Value<B>) -> PropertyDescriptor<B> {
+        self.value = Some(value);
+        self
+    }
+
+    /// Sets the get attribute of the property descriptor.
+    pub fn with_get(mut self, get: Value<B>) -> PropertyDescriptor<B> {
+        self.get = Some(get);
+        self
+    }
+
+    /// Sets the set attribute of the property descriptor.
+    pub fn with_set(mut self, set: Value<B>) -> PropertyDescriptor<B> {
+        self.set = Some(set);
+        self
+    }
+
+    /// Returns the configurable attribute of the property descriptor, if any.
+    pub fn configurable(&self) -> Option<bool> {
+        self.configurable
+    }
+
+    /// Returns the enumerable attribute of the property descriptor, if any.
+    pub fn enumerable(&self) -> Option<bool> {
+        self.enumerable
+    }
+
+    /// Returns the writable attribute of the property descriptor, if any.
+    pub fn writable(&self) -> Option<bool> {
+        self.writable
+    }
+
+    /// Returns the value attribute of the property descriptor, if any.
+    pub fn value(&self) -> Option<&Value<B>> {
+        self.value.as_ref()
+    }
+
+    /// Returns the get attribute of the property descriptor, if any.
+    pub fn get(&self) -> Option<&Value<B>> {
+        self.get.as_ref()
+    }
+
+    /// Returns the set attribute of the property descriptor, if any.
+    pub fn set(&self) -> Option<&Value<B>> {
+        self.set.as_ref()
+    }
+
+    /// Returns `true` if the property descriptor is an accessor property
+    /// descriptor. Returns `false` otherwise.
+    pub fn is_accessor_descriptor(&self) -> bool {
+        self.get.is_some() || self.set.is_some()
+    }
+
+    /// Returns `true` if the property descriptor is a data property
+    /// descriptor. Returns `false` otherwise.
+    pub fn is_data_descriptor(&self) -> bool {
+        self.writable.is_some() || self.value.is_some()
+    }
+
+    /// Returns `true` if the property descriptor is a generic property
+    /// descriptor. Returns `false` otherwise.
+    pub fn is_generic_descriptor(&self) -> bool {
+        !self.is_accessor_descriptor() && !self.is_data_descriptor()
+    }
+
+    /// Checks that the property descriptor is valid; that is, that it is not
+    /// both a data and an accessor property descriptor, and that its getter
+    /// and setter, if any, are either wrappers to a callable object or
+    /// `Undefined`.
+    ///
+    /// # Errors
+    /// If the property descriptor is not valid, returns `DescriptorNotValid`.
+    pub fn validate(&self) -> Fallible<()> {
+        let is_callable_or_undefined = |value: &Option<Value<B>>| match value {
+            Some(Value::Object(object)) => object.is_callable(),
+            Some(Value::Undefined) | None => true,
+            Some(_) => false
+        };
+        if self.is_data_descriptor() && self.is_accessor_descriptor() {
+            return Err(Error::DescriptorNotValid);
+        }
+        if !is_callable_or_undefined(&self.get) || !is_callable_or_undefined(&self.set) {
+            return Err(Error::DescriptorNotValid);
+        }
+        Ok(())
+    }
+}
+
+impl<B: Backend> Clone for PropertyDescriptor<B> {
+    fn clone(&self) -> PropertyDescriptor<B> {
+        PropertyDescriptor {
+            configurable: self.configurable,
+            enumerable: self.enumerable,
+            writable: self.writable,
+            value: self.value.clone(),
+            get: self.get.clone(),
+            set: self.set.clone()
+        }
+    }
+}
+
+impl<B: Backend> fmt::Debug for PropertyDescriptor<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("PropertyDescriptor")
+                 .field("configurable", &self.configurable)
+                 .field("enumerable", &self.enumerable)
+                 .field("writable", &self.writable)
+                 .field("value", &self.value)
+                 .field("get", &self.get)
+                 .field("set", &self.set)
+                 .finish()
+    }
+}
+
+/// A property key. This is either a string, a wrapper to a symbol, or an array
+/// index.
+///
+/// A string that is the canonical form of an array index, such as `"0"`,
+/// denotes the same property as the corresponding index key. Converting such a
+/// string into a key with `From` yields the index key.
+pub enum PropertyKey<B: Backend> {
+    String(String),
+    Symbol(Symbol<B>),
+    Index(u32)
+}
+
+impl<B: Backend> PropertyKey<B> {
+    /// Returns the array index denoted by the given `string`. If the string is
+    /// not the canonical form of an array index, returns `None` instead.
+    pub fn parse_index(string: &str) -> Option<u32> {
+        if string.is_empty() || (string.len() > 1 && string.starts_with('0')) ||
+           !string.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        string.parse().ok().filter(|&index| index != u32::MAX)
+    }
+}
+
+impl<B: Backend> Clone for PropertyKey<B> {
+    fn clone(&self) -> PropertyKey<B> {
+        match self {
+            PropertyKey::String(key) => PropertyKey::String(key.clone()),
+            PropertyKey::Symbol(key) => PropertyKey::Symbol(key.clone()),
+            PropertyKey::Index(key) => PropertyKey::Index(*key)
+        }
+    }
+}
+
+impl<B: Backend> PartialEq for PropertyKey<B> {
+    fn eq(&self, other: &PropertyKey<B>) -> bool {
+        match (self, other) {
+            (PropertyKey::String(a), PropertyKey::String(b)) => a == b,
+            (PropertyKey::Symbol(a), PropertyKey::Symbol(b)) => a == b,
+            (PropertyKey::Index(a), PropertyKey::Index(b)) => a == b,
+            _ => false
+        }
+    }
+}
+
+impl<B: Backend> Eq for PropertyKey<B> {}
+
+impl<B: Backend> fmt::Debug for PropertyKey<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PropertyKey::String(key) => formatter.debug_tuple("String").field(key).finish(),
+            PropertyKey::Symbol(key) => formatter.debug_tuple("Symbol").field(key).finish(),
+            PropertyKey::Index(key) => formatter.debug_tuple("Index").field(key).finish()
+        }
+    }
+}
+
+impl<'a, B: Backend> From<&'a str> for PropertyKey<B> {
+    fn from(key: &'a str) -> PropertyKey<B> {
+        match PropertyKey::<B>::parse_index(key) {
+            Some(index) => PropertyKey::Index(index),
+            None => PropertyKey::String(key.to_owned())
+        }
+    }
+}
+
+impl<B: Backend> From<String> for PropertyKey<B> {
+    fn from(key: String) -> PropertyKey<B> {
+        match PropertyKey::<B>::parse_index(&key) {
+            Some(index) => PropertyKey::Index(index),
+            None => PropertyKey::String(key)
+        }
+    }
+}
+
+impl<B: Backend> From<u32> for PropertyKey<B> {
+    /// Returns an index key for the given `key`, unless it is `u32::MAX`,
+    /// which is not an array index, in which case returns a string key.
+    fn from(key: u32) -> PropertyKey<B> {
+        if key == u32::MAX {
+            PropertyKey::String(key.to_string())
+        } else {
+            PropertyKey::Index(key)
+        }
+    }
+}
+
+impl<B: Backend> From<Symbol<B>> for PropertyKey<B> {
+    fn from(key: Symbol<B>) -> PropertyKey<B> {
+        PropertyKey::Symbol(key)
+    }
+}
+
+/// An enum describing the kind of a function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FunctionKind {
+    /// An ordinary function, declared with `function`.
+    Normal,
+
+    /// An arrow function.
+    Arrow,
+
+    /// A method, declared in an object literal or a class body.
+    Method,
+
+    /// A getter, declared with `get`.
+    Getter,
+
+    /// A setter, declared with `set`.
+    Setter,
+
+    /// A class constructor, declared with `class`.
+    ClassConstructor,
+
+    /// A generator function, declared with `function*`.
+    Generator,
+
+    /// An async function, declared with `async function`.
+    Async,
+
+    /// An async arrow function.
+    AsyncArrow,
+
+    /// An async generator function, declared with `async function*`.
+    AsyncGenerator
+}
+
+/// An enum describing the type of the elements of a typed array.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ElementType {
+    Int8,
+    Uint8,
+    Uint8Clamped,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Float32,
+    Float64,
+    BigInt64,
+    BigUint64
+}
+
+impl ElementType {
+    /// Returns the number of bytes occupied by an element of this type.
+    pub fn size(&self) -> usize {
+        match *self {
+            ElementType::Int8 | ElementType::Uint8 | ElementType::Uint8Clamped => 1,
+            ElementType::Int16 | ElementType::Uint16 => 2,
+            ElementType::Int32 | ElementType::Uint32 | ElementType::Float32 => 4,
+            ElementType::Float64 | ElementType::BigInt64 | ElementType::BigUint64 => 8
+        }
+    }
+}
+
+/// An entry of a `WeakMap` or `WeakSet`, as a pair of a key and a value. The
+/// value is `None` for a `WeakSet`.
+pub type WeakEntry<B> = (Object<B>, Option<Value<B>>);
+
+/// An enum describing the state of a promise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PromiseState {
+    /// The promise has neither been fulfilled nor rejected.
+    Pending,
+
+    /// The promise has been fulfilled with a value.
+    Fulfilled,
+
+    /// The promise has been rejected with a reason.
+    Rejected
 }
 
-/// A wrapper to an object in the debuggee.
-pub struct Object;
+wrapper! {
+    /// A wrapper to an object in the debuggee.
+    pub struct Object;
+}
+
+impl<B: Backend> Object<B> {
+    /// Returns the frames that were on the stack when the wrapped object was
+    /// allocated, youngest first. If the wrapped object was not allocated by
+    /// code running while allocations were recorded, returns `None` instead.
+    pub fn allocation_site(&self) -> Option<Vec<SavedFrame>> {
+        B::object_allocation_site(&self.handle)
+    }
 
-impl Object {
     /// Returns a wrapper to the global environment of the wrapped object.
     ///
     /// # Errors
     /// If the wrapped object does not have a global environment because it is
     /// not a global, returns `ObjectNotGlobal`.
-    pub fn as_environment(&self) -> Fallible<Environment> {
-        unimplemented!()
+    pub fn as_environment(&self) -> Fallible<Environment<B>> {
+        B::object_as_environment(&self.handle)
     }
 
     /// If the wrapped object is a bound function, returns the arguments to
     /// which it was bound. Otherwise, returns `None`.
-    pub fn bound_arguments(&self) -> Option<Vec<Value>> {
-        unimplemented!()
+    pub fn bound_arguments(&self) -> Option<Vec<Value<B>>> {
+        B::object_bound_arguments(&self.handle)
     }
 
     /// If the wrapped object is a bound function, returns the target function;
     /// that is, the function that was bound to a particular this value and
     /// arguments. Otherwise, returns `None`.
-    pub fn bound_target_function(&self) -> Option<Object> {
-        unimplemented!()
+    pub fn bound_target_function(&self) -> Option<Object<B>> {
+        B::object_bound_target_function(&self.handle)
     }
 
     /// If the wrapped object is a bound function, returns the this value to
     /// which it was bound. Otherwise, returns `Undefined`.
-    pub fn bound_this(&self) -> Value {
-        unimplemented!()
+    pub fn bound_this(&self) -> Value<B> {
+        B::object_bound_this(&self.handle)
+    }
+
+    /// If the wrapped object is a typed array or a `DataView`, returns a
+    /// wrapper to the `ArrayBuffer` or `SharedArrayBuffer` it is a view on.
+    /// Otherwise, returns `None`.
+    pub fn buffer(&self) -> Option<Object<B>> {
+        B::object_buffer(&self.handle)
+    }
+
+    /// If the wrapped object is an `ArrayBuffer`, a `SharedArrayBuffer`, a
+    /// typed array or a `DataView`, returns the number of bytes it covers.
+    /// This is 0 if the underlying buffer is detached. Otherwise, returns
+    /// `None`.
+    pub fn byte_length(&self) -> Option<usize> {
+        B::object_byte_length(&self.handle)
+    }
+
+    /// If the wrapped object is a typed array or a `DataView`, returns the
+    /// offset in bytes at which it starts in its buffer. This is 0 if the
+    /// buffer is detached. Otherwise, returns `None`.
+    pub fn byte_offset(&self) -> Option<usize> {
+        B::object_byte_offset(&self.handle)
     }
 
     /// Calls the wrapped object with the given `this` value and `arguments`.
@@ -430,8 +1601,8 @@ impl Object {
     /// # Errors
     /// If the wrapped object could not be called because it is not callable,
     /// returns `ObjectNotCallable`.
-    pub fn call(&self, this: &Value, arguments: &[Value]) -> Fallible<CompletionValue> {
-        unimplemented!();
+    pub fn call(&self, this: &Value<B>, arguments: &[Value<B>]) -> Fallible<CompletionValue<B>> {
+        B::object_call(&self.handle, this, arguments)
     }
 
     /// Calls the wrapped object as a constructor with the given `arguments`.
@@ -440,23 +1611,70 @@ impl Object {
     /// # Errors
     /// If the wrapped object could not be called because it is not callable,
     /// returns `ObjectNotCallable`.
-    pub fn construct(&self, arguments: &[Value]) -> Fallible<CompletionValue> {
-        unimplemented!();
+    pub fn construct(&self, arguments: &[Value<B>]) -> Fallible<CompletionValue<B>> {
+        B::object_construct(&self.handle, arguments)
     }
 
     /// Returns the value of the internal property [[Class]] of the wrapped
     /// object.
     pub fn class(&self) -> String {
-        unimplemented!()
+        B::object_class(&self.handle)
+    }
+
+    /// If the wrapped object is a function in the debuggee, returns the scopes
+    /// it closes over; that is, the environment in which it was created and
+    /// the environments enclosing it, innermost first, up to but not including
+    /// the environment of its global. Otherwise, returns `None`.
+    ///
+    /// # Errors
+    /// If this method would cause the debuggee to run because the object
+    /// reflected by one of the environments is a proxy, returns
+    /// `DebuggeeWouldRun`.
+    ///
+    /// If one of the environments could not be inspected because it is not a
+    /// debuggee environment, returns `EnvironmentNotDebuggee`.
+    pub fn closure_scopes(&self) -> Fallible<Option<Vec<ClosureScope<B>>>> {
+        let mut environment = match self.environment() {
+            Some(environment) => environment,
+            None => return Ok(None)
+        };
+        let global = self.global().as_environment().ok();
+        let mut scopes = Vec::new();
+        while Some(&environment) != global.as_ref() {
+            let parent = environment.parent()?;
+            let optimized_out = environment.is_optimized_out()?;
+            let mut bindings = Vec::new();
+            for name in environment.names()? {
+                let value = if optimized_out { None } else { Some(environment.get_variable(&name)?) };
+                bindings.push((name, value));
+            }
+            scopes.push(ClosureScope {
+                environment_type: environment.get_type()?,
+                environment,
+                optimized_out,
+                bindings
+            });
+            environment = match parent {
+                Some(parent) => parent,
+                None => break
+            };
+        }
+        Ok(Some(scopes))
+    }
+
+    /// If the wrapped object is a `Map`, `Set`, `WeakMap` or `WeakSet`,
+    /// returns the number of entries in it. Otherwise, returns `None`.
+    pub fn collection_size(&self) -> Option<usize> {
+        B::object_collection_size(&self.handle)
     }
 
     /// If the wrapped object is a function with a display name, returns the
     /// display name of the function. Otherwise, returns `None`.
     pub fn display_name(&self) -> Option<String> {
-        unimplemented!()
+        B::object_display_name(&self.handle)
     }
 
-    /// Defines a property with the given `name` on the wrapped object, as
+    /// Defines a property with the given `key` on the wrapped object, as
     /// described by the given `descriptor`. If there already is such a
     /// property, modifies the existing property. Otherwise, adds a new
     /// property.
@@ -465,16 +1683,20 @@ impl Object {
     /// If this method would cause the debuggee to run because the wrapped
     /// object is a proxy, returns `DebuggeeWouldRun`.
     ///
+    /// If the given `descriptor` is not valid, as checked by
+    /// `PropertyDescriptor::validate`, returns `DescriptorNotValid`.
+    ///
     /// If a new property could not be added because the wrapped object is not
     /// extensible, returns `ObjectNotExtensible`.
     ///
     /// If an existing property could not be modified because the property is
     /// not configurable, returns `PropertyNotConfigurable`.
-    pub fn define_property(&self, name: &str, descriptor: &PropertyDescriptor) -> Fallible<()> {
-        unimplemented!()
+    pub fn define_property(&self, key: &PropertyKey<B>, descriptor: &PropertyDescriptor<B>) -> Fallible<()> {
+        descriptor.validate()?;
+        B::object_define_property(&self.handle, key, descriptor)
     }
 
-    /// Deletes the property with the given `name` from the wrapped object.
+    /// Deletes the property with the given `key` from the wrapped object.
     ///
     /// # Errors
     /// If this method would cause the debuggee to run because the wrapped
@@ -482,15 +1704,49 @@ impl Object {
     ///
     /// If the property could not be deleted because it is non-configurable,
     /// returns `PropertyNotConfigurable`.
-    pub fn delete_property(&self, name: &str) -> Fallible<()> {
-        unimplemented!()
+    pub fn delete_property(&self, key: &PropertyKey<B>) -> Fallible<()> {
+        B::object_delete_property(&self.handle, key)
+    }
+
+    /// If the wrapped object is a typed array, returns the type of its
+    /// elements. Otherwise, returns `None`.
+    pub fn element_type(&self) -> Option<ElementType> {
+        B::object_element_type(&self.handle)
     }
 
     /// If the wrapped object is a function in the debuggee, returns a wrapper
     /// to the environment in which the function was created. Otherwise, returns
     /// `None`.
-    pub fn environment(&self) -> Option<Environment> {
-        unimplemented!()
+    pub fn environment(&self) -> Option<Environment<B>> {
+        B::object_environment(&self.handle)
+    }
+
+    /// If the wrapped object is an error, returns the location at which it was
+    /// created. Otherwise, or if the location is unknown, returns `None`.
+    /// This never calls any getters on the wrapped object.
+    pub fn error_location(&self) -> Option<SourceLocation> {
+        B::object_error_location(&self.handle)
+    }
+
+    /// If the wrapped object is an error, returns the message with which it
+    /// was created. Otherwise, returns `None`. This never calls any getters
+    /// on the wrapped object.
+    pub fn error_message(&self) -> Option<String> {
+        B::object_error_message(&self.handle)
+    }
+
+    /// If the wrapped object is an error, returns the name of its type, such
+    /// as `"TypeError"`. Otherwise, returns `None`. This never calls any
+    /// getters on the wrapped object.
+    pub fn error_name(&self) -> Option<String> {
+        B::object_error_name(&self.handle)
+    }
+
+    /// If the wrapped object is an error, returns the frames that were on the
+    /// stack when it was created, youngest first. Otherwise, returns `None`.
+    /// This never calls any getters on the wrapped object.
+    pub fn error_stack(&self) -> Option<Vec<SavedFrame>> {
+        B::object_error_stack(&self.handle)
     }
 
     /// Executes the given `code` in the global environment of the wrapped
@@ -500,8 +1756,8 @@ impl Object {
     /// # Errors
     /// If the wrapped object does not have a global environment because it is
     /// not a global, returns `ObjectNotGlobal`.
-    pub fn execute_in_global(&self, code: &str) -> Fallible<CompletionValue> {
-        unimplemented!()
+    pub fn execute_in_global(&self, code: &str) -> Fallible<CompletionValue<B>> {
+        B::object_execute_in_global(&self.handle, code)
     }
 
     /// Executes the given `code` in the global environment of the wrapped
@@ -512,8 +1768,8 @@ impl Object {
     /// # Errors
     /// If the wrapped object does not have a global environment because it is
     /// not a global, returns `ObjectNotGlobal`.
-    pub fn execute_in_global_with_bindings(&self, code: &str, bindings: &BTreeMap<String, Value>) -> Fallible<CompletionValue> {
-        unimplemented!()
+    pub fn execute_in_global_with_bindings(&self, code: &str, bindings: &BTreeMap<String, Value<B>>) -> Fallible<CompletionValue<B>> {
+        B::object_execute_in_global_with_bindings(&self.handle, code, bindings)
     }
 
     /// Freezes the wrapped object; that is, prevents extensions on it, and
@@ -523,55 +1779,144 @@ impl Object {
     /// If this method would cause the debuggee to run because the wrapped
     /// object is a proxy, returns `DebuggeeWouldRun`.
     pub fn freeze(&self) -> Fallible<()> {
-        unimplemented!()
+        B::object_freeze(&self.handle)
+    }
+
+    /// If the wrapped object is a function, returns its kind. If it is a bound
+    /// function, returns the kind of its target function instead. Otherwise,
+    /// returns `None`.
+    pub fn function_kind(&self) -> Option<FunctionKind> {
+        B::object_function_kind(&self.handle)
+    }
+
+    /// If the wrapped object is a function in the debuggee, returns the
+    /// location of its code in the source of its script. Lines count from 1 at
+    /// the start of the text of the source, and columns count characters from
+    /// the start of their line, from 0. For an inline script, the location is
+    /// thus relative to the source rather than the document that contains it.
+    /// Otherwise, or if the script has no source, returns `None`.
+    pub fn function_location(&self) -> Option<FunctionLocation<B>> {
+        let script = self.script()?;
+        let source = script.source()?;
+        let text: Vec<char> = source.text().chars().collect();
+        let start = (script.source_start() as usize).min(text.len());
+        let end = (start + script.source_length() as usize).min(text.len());
+        // Returns the line and column just past the given characters.
+        let position = |chars: &[char]| {
+            let line = chars.iter().filter(|&&c| c == '\n').count() as u32 + 1;
+            let column = chars.iter().rev().take_while(|&&c| c != '\n').count() as u32;
+            (line, column)
+        };
+        let (start_line, start_column) = position(&text[..start]);
+        let (end_line, end_column) = position(&text[..end]);
+        Some(FunctionLocation {
+            source,
+            start_line,
+            start_column,
+            end_line,
+            end_column
+        })
+    }
+
+    /// If the wrapped object is a function in the debuggee, returns the text
+    /// of its code; that is, the characters of the source of its script that
+    /// are spanned by the script. Otherwise, or if the script has no source,
+    /// returns `None`.
+    pub fn function_source_text(&self) -> Option<String> {
+        let script = self.script()?;
+        let text = script.source()?.text();
+        let start = script.source_start() as usize;
+        Some(text.chars().skip(start).take(script.source_length() as usize).collect())
     }
 
-    /// Returns a property descriptor for the own property with the given
-    /// `name` on the wrapped object.
+    /// Returns a property descriptor for the own property with the given `key`
+    /// on the wrapped object. If there is no such property, returns
+    /// `None` instead.
     ///
     /// # Errors
     /// If this method would cause the debuggee to run because the wrapped
     /// object is a proxy, returns `DebuggeeWouldRun`.
-    pub fn get_own_property_descriptor(&self, name: &str) -> Fallible<PropertyDescriptor> {
-        unimplemented!()
+    pub fn get_own_property_descriptor(&self, key: &PropertyKey<B>) -> Fallible<Option<PropertyDescriptor<B>>> {
+        B::object_get_own_property_descriptor(&self.handle, key)
     }
 
-    /// Returns the names of the own properties of the wrapped object.
+    /// Returns the keys of the own properties of the wrapped object that are
+    /// not keyed by a symbol. Index keys come first, in ascending order,
+    /// followed by string keys, in the order in which they were added.
     ///
     /// # Errors
     /// If this method would cause the debuggee to run because the wrapped
     /// object is a proxy, returns `DebuggeeWouldRun`.
-    pub fn get_own_property_names(&self) -> Fallible<Vec<String>> {
-        unimplemented!()
+    pub fn get_own_property_names(&self) -> Fallible<Vec<PropertyKey<B>>> {
+        B::object_get_own_property_names(&self.handle)
+    }
+
+    /// Returns wrappers to the symbols that key the own properties of the
+    /// wrapped object, in the order in which they were added.
+    ///
+    /// # Errors
+    /// If this method would cause the debuggee to run because the wrapped
+    /// object is a proxy, returns `DebuggeeWouldRun`.
+    pub fn get_own_property_symbols(&self) -> Fallible<Vec<Symbol<B>>> {
+        B::object_get_own_property_symbols(&self.handle)
+    }
+
+    /// Returns the names of the private fields of the wrapped object, along
+    /// with their values. Private names include their leading `#`, which
+    /// distinguishes them from the keys of ordinary properties. This never
+    /// runs the debuggee.
+    pub fn get_private_fields(&self) -> Vec<(String, Value<B>)> {
+        B::object_get_private_fields(&self.handle)
+    }
+
+    /// Returns the names of the private methods and accessors of the wrapped
+    /// object, along with descriptors for them. A private method is described
+    /// by a non-writable data descriptor whose value is the method, and a
+    /// private accessor by an accessor descriptor. Private names include
+    /// their leading `#`. This never runs the debuggee.
+    pub fn get_private_methods(&self) -> Vec<(String, PropertyDescriptor<B>)> {
+        B::object_get_private_methods(&self.handle)
     }
 
     /// Returns a wrapper to the prototype of the wrapped object. If the
     /// wrapped object does not have a prototype, returns `None` instead.
-    pub fn get_prototype_of(&self) -> Option<Object> {
-        unimplemented!()
+    pub fn get_prototype_of(&self) -> Option<Object<B>> {
+        B::object_get_prototype_of(&self.handle)
     }
 
     /// Returns a wrapper to the global of the wrapped object.
-    pub fn global(&self) -> Object {
-        unimplemented!()
+    pub fn global(&self) -> Object<B> {
+        B::object_global(&self.handle)
     }
 
     /// Returns `true` if the wrapped object is an arrow function. Returns
     /// `false` otherwise.
     pub fn is_arrow_function(&self) -> bool {
-        unimplemented!()
+        B::object_is_arrow_function(&self.handle)
     }
 
     /// Returns `true` if the wrapped object is a bound function. Returns
     /// `false` otherwise.
     pub fn is_bound_function(&self) -> bool {
-        unimplemented!()
+        B::object_is_bound_function(&self.handle)
+    }
+
+    /// Returns `true` if the wrapped object is a class constructor, or a bound
+    /// function whose target function is one. Returns `false` otherwise.
+    pub fn is_class_constructor(&self) -> bool {
+        self.function_kind() == Some(FunctionKind::ClassConstructor)
     }
 
     /// Returns `true` if the wrapped object is callable. Returns `false`
     /// otherwise.
     pub fn is_callable(&self) -> bool {
-        unimplemented!()
+        B::object_is_callable(&self.handle)
+    }
+
+    /// Returns `true` if the wrapped object is a detached `ArrayBuffer`, or a
+    /// typed array or `DataView` on one. Returns `false` otherwise.
+    pub fn is_detached(&self) -> bool {
+        B::object_is_detached(&self.handle)
     }
 
     /// Returns `true` if the wrapped object is extensible; that is, if
@@ -581,7 +1926,7 @@ impl Object {
     /// If this method would cause the debuggee to run because the wrapped
     /// object is a proxy, returns `DebuggeeWouldRun`.
     pub fn is_extensible(&self) -> Fallible<bool> {
-        unimplemented!()
+        B::object_is_extensible(&self.handle)
     }
 
     /// Returns `true` if the wrapped object is frozen; that is, if it is not
@@ -592,7 +1937,26 @@ impl Object {
     /// If this method would cause the debuggee to run because the wrapped
     /// object is a proxy, returns `DebuggeeWouldRun`.
     pub fn is_frozen(&self) -> Fallible<bool> {
-        unimplemented!()
+        B::object_is_frozen(&self.handle)
+    }
+
+    /// Returns `true` if the wrapped object is a function that is implemented
+    /// natively by the engine, rather than by code in a script. Bound
+    /// functions are not considered native. Returns `false` otherwise.
+    pub fn is_native(&self) -> bool {
+        B::object_is_native(&self.handle)
+    }
+
+    /// Returns `true` if the wrapped object is a proxy, whether or not it has
+    /// been revoked. Returns `false` otherwise.
+    pub fn is_proxy(&self) -> bool {
+        B::object_is_proxy(&self.handle)
+    }
+
+    /// Returns `true` if the wrapped object is a proxy that has been revoked.
+    /// Returns `false` otherwise.
+    pub fn is_revoked_proxy(&self) -> bool {
+        B::object_is_revoked_proxy(&self.handle)
     }
 
     /// Returns `true` if the wrapped object is sealed; that is, if it is not
@@ -603,19 +1967,28 @@ impl Object {
     /// If this method would cause the debuggee to run because the wrapped
     /// object is a proxy, returns `DebuggeeWouldRun`.
     pub fn is_sealed(&self) -> Fallible<bool> {
-        unimplemented!()
+        B::object_is_sealed(&self.handle)
+    }
+
+    /// If the wrapped object is a `Map`, returns the entries in it whose index
+    /// in insertion order lies within the given `range`, as pairs of a key and
+    /// a value. Otherwise, returns `None`. The parts of the range past the end
+    /// of the map are ignored. This never calls any methods on the wrapped
+    /// object.
+    pub fn map_entries(&self, range: Range<usize>) -> Option<Vec<(Value<B>, Value<B>)>> {
+        B::object_map_entries(&self.handle, range)
     }
 
     /// If the wrapped object is a named function, returns the name of the
     /// function. Otherwise, returns `None`.
     pub fn name(&self) -> Option<String> {
-        unimplemented!()
+        B::object_name(&self.handle)
     }
 
     /// If the wrapped object is a function in the debuggee, returns the names
     /// of the parameters of the function. Otherwise, returns `None`.
     pub fn parameter_names(&self) -> Option<Vec<String>> {
-        unimplemented!()
+        B::object_parameter_names(&self.handle)
     }
 
     /// Prevents extensions on the wrapped object; that is, prevents properties
@@ -625,7 +1998,69 @@ impl Object {
     /// If this method would cause the debuggee to run because the wrapped
     /// object is a proxy, returns `DebuggeeWouldRun`.
     pub fn prevent_extensions(&self) -> Fallible<()> {
-        unimplemented!()
+        B::object_prevent_extensions(&self.handle)
+    }
+
+    /// If the wrapped object is a promise, returns the frames that were on the
+    /// stack when it was allocated, youngest first. Otherwise, or if it was
+    /// not allocated by code in the debuggee, returns `None`.
+    pub fn promise_allocation_site(&self) -> Option<Vec<SavedFrame>> {
+        B::object_promise_allocation_site(&self.handle)
+    }
+
+    /// If the wrapped object is a promise, returns wrappers to the promises
+    /// that wait for it to settle. Otherwise, returns `None`.
+    pub fn promise_dependent_promises(&self) -> Option<Vec<Object<B>>> {
+        B::object_promise_dependent_promises(&self.handle)
+    }
+
+    /// If the wrapped object is a rejected promise, returns the reason with
+    /// which it was rejected. Otherwise, returns `None`.
+    pub fn promise_reason(&self) -> Option<Value<B>> {
+        B::object_promise_reason(&self.handle)
+    }
+
+    /// If the wrapped object is a settled promise, returns the frames that were
+    /// on the stack when it was settled, youngest first. Otherwise, or if it
+    /// was not settled by code in the debuggee, returns `None`.
+    pub fn promise_resolution_site(&self) -> Option<Vec<SavedFrame>> {
+        B::object_promise_resolution_site(&self.handle)
+    }
+
+    /// If the wrapped object is a promise, returns its state. Otherwise,
+    /// returns `None`.
+    pub fn promise_state(&self) -> Option<PromiseState> {
+        B::object_promise_state(&self.handle)
+    }
+
+    /// If the wrapped object is a fulfilled promise, returns the value with
+    /// which it was fulfilled. Otherwise, returns `None`.
+    pub fn promise_value(&self) -> Option<Value<B>> {
+        B::object_promise_value(&self.handle)
+    }
+
+    /// If the wrapped object is a proxy that has not been revoked, returns a
+    /// wrapper to its handler. Otherwise, returns `None`. This never calls
+    /// any of the traps of the handler.
+    pub fn proxy_handler(&self) -> Option<Object<B>> {
+        B::object_proxy_handler(&self.handle)
+    }
+
+    /// If the wrapped object is a proxy that has not been revoked, returns a
+    /// wrapper to its target. Otherwise, returns `None`. This never calls any
+    /// of the traps of the handler.
+    pub fn proxy_target(&self) -> Option<Object<B>> {
+        B::object_proxy_target(&self.handle)
+    }
+
+    /// If the wrapped object is an `ArrayBuffer`, a `SharedArrayBuffer`, a
+    /// typed array or a `DataView`, returns the bytes it covers whose offset
+    /// lies within the given `range`. The offsets are relative to the start of
+    /// the wrapped object rather than its buffer. Otherwise, returns `None`.
+    /// The parts of the range past the end of the wrapped object are ignored.
+    /// This never runs the debuggee.
+    pub fn read_bytes(&self, range: Range<usize>) -> Option<Vec<u8>> {
+        B::object_read_bytes(&self.handle, range)
     }
 
     /// Seals the wrapped object; that is, prevents extensions on it, and makes
@@ -635,28 +2070,127 @@ impl Object {
     /// If this method would cause the debuggee to run because the wrapped
     /// object is a proxy, returns `DebuggeeWouldRun`.
     pub fn seal(&self) -> Fallible<()> {
-        unimplemented!()
+        B::object_seal(&self.handle)
     }
 
     /// If the wrapped object is a function in the debuggee, returns a wrapper
     /// to the script of the function. Otherwise, returns `None`.
-    pub fn script(&self) -> Option<Script> {
-        unimplemented!()
+    pub fn script(&self) -> Option<Script<B>> {
+        B::object_script(&self.handle)
+    }
+
+    /// If the wrapped object is a `Set`, returns the values in it whose index
+    /// in insertion order lies within the given `range`. Otherwise, returns
+    /// `None`. The parts of the range past the end of the set are ignored.
+    /// This never calls any methods on the wrapped object.
+    pub fn set_entries(&self, range: Range<usize>) -> Option<Vec<Value<B>>> {
+        B::object_set_entries(&self.handle, range)
+    }
+
+    /// Sets the value of the private field of the wrapped object with the
+    /// given `name`, including its leading `#`, to the given `value`. This
+    /// never runs the debuggee.
+    ///
+    /// # Errors
+    /// If the value of the private field could not be set because the wrapped
+    /// object has no private field with the given name, returns
+    /// `FieldNotFound`. This includes the case where the name is that of a
+    /// private method or accessor.
+    pub fn set_private_field(&self, name: &str, value: &Value<B>) -> Fallible<()> {
+        B::object_set_private_field(&self.handle, name, value)
+    }
+
+    /// If the wrapped object is a `WeakMap` or a `WeakSet`, returns the entries
+    /// in it whose index in insertion order lies within the given `range`.
+    /// Otherwise, returns `None`. The parts of the range past the end of the
+    /// collection are ignored. This never calls any methods on the wrapped
+    /// object.
+    pub fn weak_entries(&self, range: Range<usize>) -> Option<Vec<WeakEntry<B>>> {
+        B::object_weak_entries(&self.handle, range)
+    }
+
+    /// If the wrapped object is an `ArrayBuffer`, a `SharedArrayBuffer`, a
+    /// typed array or a `DataView`, writes the given `bytes` to it, starting
+    /// at the given `offset`. The offset is relative to the start of the
+    /// wrapped object rather than its buffer. This never runs the debuggee.
+    ///
+    /// # Errors
+    /// If the wrapped object is not an array buffer or a view on one, returns
+    /// `ObjectNotBinaryData`. If its buffer is detached, returns
+    /// `BufferDetached`. If the bytes would not fit within the wrapped
+    /// object, returns `RangeNotValid`.
+    pub fn write_bytes(&self, offset: usize, bytes: &[u8]) -> Fallible<()> {
+        B::object_write_bytes(&self.handle, offset, bytes)
     }
 }
 
 /// A trait for values that can be used as breakpoint handler.
-pub trait BreakpointHandler {
-    fn handle(&self, frame: &Frame) -> ResumptionValue;
+pub trait BreakpointHandler<B: Backend> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B>;
 }
 
-/// A wrapper to a compiled script.
-pub struct Script;
+/// An identifier for a breakpoint, as returned by `Script::set_breakpoint`.
+/// Identifiers are never reused, so a breakpoint keeps its identifier until it
+/// is cleared, and no other breakpoint gets it afterwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BreakpointId(pub u64);
+
+/// A breakpoint describes a breakpoint set in a script.
+pub struct Breakpoint<B: Backend> {
+    /// The identifier of the breakpoint.
+    pub id: BreakpointId,
+
+    /// A wrapper to the script in which the breakpoint is set.
+    pub script: Script<B>,
+
+    /// The offset in the script at which the breakpoint is set.
+    pub offset: u32,
 
-impl Script {
+    /// The line of the offset.
+    pub line: u32,
+
+    /// Whether the breakpoint is enabled. The handler of a disabled breakpoint
+    /// is not called when the breakpoint is hit.
+    pub enabled: bool,
+
+    /// The handler of the breakpoint.
+    pub handler: Rc<Box<dyn BreakpointHandler<B>>>
+}
+
+impl<B: Backend> Clone for Breakpoint<B> {
+    fn clone(&self) -> Breakpoint<B> {
+        Breakpoint {
+            id: self.id,
+            script: self.script.clone(),
+            offset: self.offset,
+            line: self.line,
+            enabled: self.enabled,
+            handler: self.handler.clone()
+        }
+    }
+}
+
+impl<B: Backend> fmt::Debug for Breakpoint<B> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("Breakpoint")
+                 .field("id", &self.id)
+                 .field("script", &self.script)
+                 .field("offset", &self.offset)
+                 .field("line", &self.line)
+                 .field("enabled", &self.enabled)
+                 .finish()
+    }
+}
+
+wrapper! {
+    /// A wrapper to a compiled script.
+    pub struct Script;
+}
+
+impl<B: Backend> Script<B> {
     /// Clears all the breakpoints in this script.
-    pub fn clear_all_breakpoints(&self) -> () {
-        unimplemented!()
+    pub fn clear_all_breakpoints(&self) {
+        B::script_clear_all_breakpoints(&self.handle)
     }
 
     /// Clears the breakpoints at the given `offset` in the wrapped script.
@@ -665,96 +2199,139 @@ impl Script {
     /// If the given `offset` is not a valid offset in the wrapped script,
     /// returns `OffsetNotValid`.
     pub fn clear_breakpoints(&self, offset: u32) -> Fallible<()> {
-        unimplemented!()
+        B::script_clear_breakpoints(&self.handle, offset)
     }
 
     /// Returns the display name of the wrapped script. If the wrapped script
     /// has no display name, returns `None` instead.
     pub fn display_name(&self) -> Option<String> {
-        unimplemented!()
+        B::script_display_name(&self.handle)
     }
 
     /// Returns a map from lines to the offsets that are entry points for each
     /// line.
     pub fn get_all_line_offsets(&self) -> BTreeMap<u32, Vec<u32>> {
-        unimplemented!()
+        B::script_get_all_line_offsets(&self.handle)
     }
 
-    /// Returns the handlers for the breakpoints at the given `offset` in the
-    /// wrapped script.
+    /// Returns the breakpoints at the given `offset` in the wrapped script, in
+    /// the order in which they were set.
     ///
     /// # Errors
     /// If the given `offset` is not a valid offset in the wrapped script,
     /// returns `OffsetNotValid`.
-    pub fn get_breakpoints(&self, offset: u32) -> Vec<Rc<Box<BreakpointHandler>>> {
-        unimplemented!()
+    pub fn get_breakpoints(&self, offset: u32) -> Fallible<Vec<Breakpoint<B>>> {
+        B::script_get_breakpoints(&self.handle, offset)
     }
 
     /// Returns the wrappers to the scripts for each function in the wrapped
     /// script.
-    pub fn get_child_scripts(&self) -> Vec<Script> {
-        unimplemented!()
+    pub fn get_child_scripts(&self) -> Vec<Script<B>> {
+        B::script_get_child_scripts(&self.handle)
     }
 
     /// Returns the offsets that are entry points for the given `line`.
     pub fn get_line_offsets(&self, line: u32) -> Vec<u32> {
-        unimplemented!()
+        B::script_get_line_offsets(&self.handle, line)
+    }
+
+    /// Returns the location in the document from which the source of the
+    /// wrapped script was loaded of the code at the given `offset`.
+    ///
+    /// # Errors
+    /// If the given `offset` is not a valid offset in the wrapped script,
+    /// returns `OffsetNotValid`.
+    pub fn get_offset_location(&self, offset: u32) -> Fallible<SourceLocation> {
+        B::script_get_offset_location(&self.handle, offset)
     }
 
     /// Returns a wrapper to the global in which the script is being executed.
-    pub fn global(&self) -> Object {
-        unimplemented!()
+    pub fn global(&self) -> Object<B> {
+        B::script_global(&self.handle)
     }
 
     /// The number of lines spanned by the code of the wrapped script in the
     /// document from which its source was loaded.
     pub fn line_count(&self) -> u32 {
-        unimplemented!()
+        B::script_line_count(&self.handle)
+    }
+
+    /// Clears the breakpoint with the given `id` in the wrapped script,
+    /// leaving any other breakpoints at the same offset in place.
+    ///
+    /// # Errors
+    /// If the wrapped script has no breakpoint with the given `id`, returns
+    /// `BreakpointNotFound`.
+    pub fn remove_breakpoint(&self, id: BreakpointId) -> Fallible<()> {
+        B::script_remove_breakpoint(&self.handle, id)
     }
 
-    /// Sets a breakpoint at the given `offset` in the wrapped script. When the
-    /// breakpoint is hit, the `handle` method of the given `handler` will be
-    /// called.
+    /// Sets a breakpoint at the given `offset` in the wrapped script, and
+    /// returns its identifier. The breakpoint is enabled. When the breakpoint
+    /// is hit, the `handle` method of the given `handler` will be called.
     ///
     /// # Errors
     /// If the given `offset` is not a valid offset in the wrapped script,
     /// returns `OffsetNotValid`.
-    pub fn set_breakpoint(&self, offset: u32, handler: Rc<Box<BreakpointHandler>>) -> Fallible<()> {
-        unimplemented!()
+    pub fn set_breakpoint(&self, offset: u32, handler: Rc<Box<dyn BreakpointHandler<B>>>) -> Fallible<BreakpointId> {
+        B::script_set_breakpoint(&self.handle, offset, handler)
+    }
+
+    /// Enables the breakpoint with the given `id` in the wrapped script if
+    /// `enabled` is `true`, or disables it otherwise.
+    ///
+    /// # Errors
+    /// If the wrapped script has no breakpoint with the given `id`, returns
+    /// `BreakpointNotFound`.
+    pub fn set_breakpoint_enabled(&self, id: BreakpointId, enabled: bool) -> Fallible<()> {
+        B::script_set_breakpoint_enabled(&self.handle, id, enabled)
     }
 
     /// Returns a wrapper to the source from which the wrapped script was
     /// compiled. If the source was not retained, returns `None` instead.
-    pub fn source(&self) -> Option<Source> {
-        unimplemented!()
+    pub fn source(&self) -> Option<Source<B>> {
+        B::script_source(&self.handle)
     }
 
     /// The number of characters spanned by the code of the wrapped script in
     /// the source from which it was compiled.
     pub fn source_length(&self) -> u32 {
-        unimplemented!()
+        B::script_source_length(&self.handle)
     }
 
     /// The index of the character at which the code of the wrapped script
     /// starts in the source from which it was compiled.
     pub fn source_start(&self) -> u32 {
-        unimplemented!()
+        B::script_source_start(&self.handle)
     }
 
     /// The line number at which the code of the wrapped script starts in the
     /// document from which its source was loaded.
     pub fn start_line(&self) -> u32 {
-        unimplemented!()
+        B::script_start_line(&self.handle)
     }
 
     /// Returns the url of the document from which the source of the wrapped
     /// script was loaded.
     pub fn url(&self) -> String {
-        unimplemented!()
+        B::script_url(&self.handle)
     }
 }
 
+/// Returns the given `script`, followed by all its descendants.
+fn descendants<B: Backend>(script: &Script<B>) -> Vec<Script<B>> {
+    let mut scripts = vec![script.clone()];
+    let mut index = 0;
+    while index < scripts.len() {
+        let children = scripts[index].get_child_scripts();
+        scripts.extend(children);
+        index += 1;
+    }
+    scripts
+}
+
 /// An enum describing how a source was introduced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum IntroductionType {
     /// A source introduced by a call to `eval`.
     Eval,
@@ -784,62 +2361,180 @@ pub enum IntroductionType {
     Worker
 }
 
-/// A wrapped to a JavaScript source.
-pub struct Source;
+wrapper! {
+    /// A wrapped to a JavaScript source.
+    pub struct Source;
+}
 
-impl Source {
+impl<B: Backend> Source<B> {
     /// Returns a unique identifier for the wrapped source. This allows
     /// different wrappers to the same source to be compared.
     pub fn canonical_id(&self) -> String {
-        unimplemented!();
+        B::source_canonical_id(&self.handle)
     }
 
     /// If the wrapped source was introduced by a DOM element, returns a wrapper
     /// to that DOM element. Otherwise, returns `None`.
-    pub fn element(&self) -> Option<Object> {
-        unimplemented!()
+    pub fn element(&self) -> Option<Object<B>> {
+        B::source_element(&self.handle)
     }
 
     /// If the wrapped source was introduced by an attribute on a DOM element,
     /// returns the name of that attribute. Otherwise, returns `None`.
     pub fn element_attribute_name(&self) -> Option<String> {
-        unimplemented!()
+        B::source_element_attribute_name(&self.handle)
     }
 
     /// If the wrapped source was introduced by a function call in the debuggee,
     /// returns the offset of the bytecode for the call. Otherwise, returns
     /// `None`.
-    pub fn introduction_offset() -> Option<u32> {
-        unimplemented!()
+    pub fn introduction_offset(&self) -> Option<u32> {
+        B::source_introduction_offset(&self.handle)
     }
 
     /// If the wrapped source was introduced by a function call in the debuggee,
     /// returns a wrapper to the script containing the call. Otherwise, returns
     /// `None`.
-    pub fn introduction_script() -> Option<Script> {
-        unimplemented!()
+    pub fn introduction_script(&self) -> Option<Script<B>> {
+        B::source_introduction_script(&self.handle)
     }
 
     /// Returns the introduction type of the wrapped source. If the introduction
     /// type of the wrapped source is unknown, returns `None` instead.
-    pub fn introduction_type() -> Option<IntroductionType> {
-        unimplemented!()
+    pub fn introduction_type(&self) -> Option<IntroductionType> {
+        B::source_introduction_type(&self.handle)
     }
 
     /// If the wrapped source is source mapped, and the URL of the source map is
     /// known, returns that URL. Otherwise, returns `None`.
     pub fn source_map_url(&self) -> Option<String> {
-        unimplemented!()
+        B::source_source_map_url(&self.handle)
     }
 
     /// Returns the text of the wrapped source.
     pub fn text(&self) -> String {
-        unimplemented!()
+        B::source_text(&self.handle)
     }
 
     /// Returns the url of the document from which the wrapped source was
     /// loaded.
     pub fn url(&self) -> String {
-        unimplemented!()
+        B::source_url(&self.handle)
+    }
+}
+
+wrapper! {
+    /// A wrapper to a symbol in the debuggee. Two wrappers compare equal if
+    /// and only if they refer to the same symbol.
+    pub struct Symbol;
+}
+
+impl<B: Backend> Symbol<B> {
+    /// Returns the description of the wrapped symbol. If the wrapped symbol
+    /// has no description, returns `None` instead.
+    pub fn description(&self) -> Option<String> {
+        B::symbol_description(&self.handle)
+    }
+
+    /// If the wrapped symbol is in the global symbol registry, returns the key
+    /// under which it is registered. Otherwise, returns `None`.
+    pub fn registry_key(&self) -> Option<String> {
+        B::symbol_registry_key(&self.handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {BigInt, Error, PropertyDescriptor, PropertyKey, Value};
+    use reference::{Reference, Runtime};
+
+    #[test]
+    fn bigint_display() {
+        assert_eq!(BigInt::new(false, Vec::new()).to_string(), "0");
+        assert_eq!(BigInt::new(true, Vec::new()).to_string(), "0");
+        assert_eq!(BigInt::from(42).to_string(), "42");
+        assert_eq!(BigInt::from(-42).to_string(), "-42");
+        assert_eq!(BigInt::from(1_000_000_000).to_string(), "1000000000");
+        assert_eq!(BigInt::new(false, vec![0, 1]).to_string(), "4294967296");
+        assert_eq!(BigInt::from(i64::MIN).to_string(), "-9223372036854775808");
+        assert_eq!(BigInt::new(false, vec![0, 0, 1]).to_string(), "18446744073709551616");
+        assert_eq!(format!("{:>4}", BigInt::from(7)), "   7");
+    }
+
+    #[test]
+    fn parse_index() {
+        let parse_index = PropertyKey::<Reference>::parse_index;
+        assert_eq!(parse_index("0"), Some(0));
+        assert_eq!(parse_index("42"), Some(42));
+        assert_eq!(parse_index("4294967294"), Some(4294967294));
+        assert_eq!(parse_index("4294967295"), None);
+        assert_eq!(parse_index("4294967296"), None);
+        assert_eq!(parse_index(""), None);
+        assert_eq!(parse_index("01"), None);
+        assert_eq!(parse_index("-1"), None);
+        assert_eq!(parse_index("+1"), None);
+        assert_eq!(parse_index("1.0"), None);
+        assert_eq!(parse_index(" 1"), None);
+        assert_eq!(parse_index("a"), None);
+    }
+
+    #[test]
+    fn property_descriptor_validate() {
+        let runtime = Runtime::new();
+        let global = runtime.new_global();
+        let function = Value::Object(runtime.new_native_function(&global, "f"));
+        let object = Value::Object(runtime.new_object(&global, "Object"));
+        assert_eq!(PropertyDescriptor::<Reference>::generic().validate(), Ok(()));
+        assert_eq!(PropertyDescriptor::<Reference>::data(Value::Null).with_writable(true).validate(), Ok(()));
+        assert_eq!(PropertyDescriptor::accessor(function.clone(), Value::Undefined).validate(), Ok(()));
+        let both = PropertyDescriptor::data(Value::Null).with_get(function.clone());
+        assert!(both.is_data_descriptor() && both.is_accessor_descriptor());
+        assert_eq!(both.validate(), Err(Error::DescriptorNotValid));
+        let writable_accessor = PropertyDescriptor::accessor(function, Value::Undefined).with_writable(false);
+        assert_eq!(writable_accessor.validate(), Err(Error::DescriptorNotValid));
+        assert_eq!(PropertyDescriptor::accessor(object, Value::Undefined).validate(), Err(Error::DescriptorNotValid));
+        assert_eq!(PropertyDescriptor::<Reference>::accessor(Value::Undefined, Value::Null).validate(),
+                   Err(Error::DescriptorNotValid));
+    }
+
+    #[test]
+    fn value_display() {
+        let number = |value: f64| Value::<Reference>::Number(value).to_string();
+        assert_eq!(number(0.0), "0");
+        assert_eq!(number(-0.0), "0");
+        assert_eq!(number(f64::NAN), "NaN");
+        assert_eq!(number(f64::NEG_INFINITY), "-Infinity");
+        assert_eq!(number(123.0), "123");
+        assert_eq!(number(-1.5), "-1.5");
+        assert_eq!(number(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(number(1e20), "100000000000000000000");
+        assert_eq!(number(1e21), "1e+21");
+        assert_eq!(number(1.25e21), "1.25e+21");
+        assert_eq!(number(0.000001), "0.000001");
+        assert_eq!(number(1e-7), "1e-7");
+        assert_eq!(number(1.5e-7), "1.5e-7");
+        assert_eq!(number(f64::MAX), "1.7976931348623157e+308");
+        assert_eq!(number(5e-324), "5e-324");
+
+        let runtime = Runtime::new();
+        let global = runtime.new_global();
+        let error = runtime.new_error(&global, "TypeError", "oops");
+        assert_eq!(Value::Object(error).to_string(), "TypeError: oops");
+        assert_eq!(Value::Object(runtime.new_object(&global, "Object")).to_string(), "[object Object]");
+        assert_eq!(Value::<Reference>::BigInt(BigInt::from(-3)).to_string(), "-3");
+        assert_eq!(Value::<Reference>::Undefined.to_string(), "undefined");
+    }
+
+    #[test]
+    fn value_is_truthy() {
+        assert!(!Value::<Reference>::Undefined.is_truthy());
+        assert!(!Value::<Reference>::Null.is_truthy());
+        assert!(!Value::<Reference>::String(String::new()).is_truthy());
+        assert!(Value::<Reference>::String("0".to_owned()).is_truthy());
+        assert!(!Value::<Reference>::Number(0.0).is_truthy());
+        assert!(!Value::<Reference>::Number(f64::NAN).is_truthy());
+        assert!(Value::<Reference>::Number(-1.0).is_truthy());
+        assert!(!Value::<Reference>::BigInt(BigInt::from(0)).is_truthy());
+        assert!(Value::<Reference>::BigInt(BigInt::from(2)).is_truthy());
     }
 }
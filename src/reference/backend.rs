@@ -0,0 +1,1194 @@
+//! The implementation of `Backend` for the reference backend.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+use {AllocationLogEntry, Backend, Breakpoint, BreakpointHandler, BreakpointId, ClassCensus,
+     CompletionValue, DebuggerStatementHandler, ElementType, EnterFrameHandler, Environment,
+     EnvironmentType, Error, ExceptionUnwindHandler, Fallible, Frame, FrameImplementation,
+     FrameType, FunctionKind, IntroductionType, NewScriptHandler, Object, ObjectQuery, PopHandler,
+     PromiseState, PropertyDescriptor, PropertyKey, SavedFrame, Script, ScriptQuery, Source,
+     SourceLocation, StepHandler, Symbol, Value, WeakEntry};
+
+use super::{Handle, Reference, to_completion_value, to_key, to_property_key, to_slot, to_value};
+use super::interpreter::{self, Completion};
+use super::state::{BreakpointData, CollectionData, Key, Property, PropertyKind, ProxyData, Slot,
+                   State};
+
+/// Returns descriptions of the breakpoints at the given `offset` in the given
+/// `script`, using the given `handle` to create wrappers.
+fn breakpoints(handle: &Handle, state: &State, script: usize, offset: u32) -> Vec<Breakpoint<Reference>> {
+    let data = &state.scripts[script];
+    data.breakpoints.get(&offset).map_or_else(Vec::new, |breakpoints| {
+        breakpoints.iter().map(|breakpoint| Breakpoint {
+            id: BreakpointId(breakpoint.id),
+            script: handle.to(script, Script::from_handle),
+            offset,
+            line: data.instructions[offset as usize].line,
+            enabled: breakpoint.enabled,
+            handler: breakpoint.handler.clone()
+        }).collect()
+    })
+}
+
+/// Returns the part of the given `entries` that lies within the given `range`,
+/// ignoring the parts of the range past the end of the entries.
+fn page<T>(entries: &[T], range: Range<usize>) -> &[T] {
+    let start = range.start.min(entries.len());
+    let end = range.end.max(start).min(entries.len());
+    &entries[start..end]
+}
+
+impl Handle {
+    fn to<T>(&self, index: usize, wrap: fn(Handle) -> T) -> T {
+        wrap(Handle::new(&self.state, index))
+    }
+
+    fn value(&self, slot: &Slot) -> Value<Reference> {
+        to_value(&self.state, slot)
+    }
+
+    fn property_key(&self, key: &Key) -> PropertyKey<Reference> {
+        to_property_key(&self.state, key)
+    }
+
+    fn completion_value(&self, completion: &Completion) -> CompletionValue<Reference> {
+        to_completion_value(&self.state, completion)
+    }
+
+    /// Returns an error if the wrapped environment is not a debuggee
+    /// environment.
+    fn check_environment(&self) -> Fallible<()> {
+        let state = self.state.borrow();
+        if state.is_debuggee(state.environments[self.index].global) {
+            Ok(())
+        } else {
+            Err(Error::EnvironmentNotDebuggee)
+        }
+    }
+
+    /// Returns `true` if the wrapped frame is a debuggee frame.
+    fn is_debuggee_frame(&self) -> bool {
+        let state = self.state.borrow();
+        state.is_debuggee(state.frames[self.index].global)
+    }
+
+    /// Returns an error if the wrapped object is not a global.
+    fn check_global(&self) -> Fallible<usize> {
+        self.state.borrow().objects[self.index].environment.ok_or(Error::ObjectNotGlobal)
+    }
+
+    /// Returns `DebuggeeWouldRun` if the wrapped object is a proxy, since a
+    /// real engine would have to call the traps of its handler.
+    fn check_proxy(&self) -> Fallible<()> {
+        if self.state.borrow().objects[self.index].proxy.is_some() {
+            Err(Error::DebuggeeWouldRun)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns `DebuggeeWouldRun` if the object reflected by the wrapped
+    /// environment is a proxy.
+    fn check_environment_proxy(&self) -> Fallible<()> {
+        let object = self.state.borrow().environments[self.index].object;
+        match object {
+            Some(object) => Handle::new(&self.state, object).check_proxy(),
+            None => Ok(())
+        }
+    }
+
+    fn proxy(&self, select: fn(&ProxyData) -> usize) -> Option<Object<Reference>> {
+        let state = self.state.borrow();
+        match state.objects[self.index].proxy {
+            Some(ref proxy) if !proxy.revoked => Some(self.to(select(proxy), Object::from_handle)),
+            _ => None
+        }
+    }
+
+    fn set_integrity(&self, frozen: bool) {
+        let mut state = self.state.borrow_mut();
+        let object = &mut state.objects[self.index];
+        object.extensible = false;
+        for &mut (_, ref mut property) in &mut object.properties {
+            property.configurable = false;
+            if let PropertyKind::Data { ref mut writable, .. } = property.kind {
+                *writable = *writable && !frozen;
+            }
+        }
+    }
+
+    fn has_integrity(&self, frozen: bool) -> bool {
+        let state = self.state.borrow();
+        let object = &state.objects[self.index];
+        !object.extensible && object.properties.iter().all(|(_, property)| {
+            !property.configurable && match property.kind {
+                PropertyKind::Data { writable, .. } => !(frozen && writable),
+                PropertyKind::Accessor { .. } => true
+            }
+        })
+    }
+}
+
+fn to_descriptor(handle: &Handle, property: &Property) -> PropertyDescriptor<Reference> {
+    let (writable, value, get, set) = match property.kind {
+        PropertyKind::Data { ref value, writable } => {
+            (Some(writable), Some(handle.value(value)), None, None)
+        },
+        PropertyKind::Accessor { ref get, ref set } => {
+            (None, None, Some(handle.value(get)), Some(handle.value(set)))
+        }
+    };
+    PropertyDescriptor {
+        configurable: Some(property.configurable),
+        enumerable: Some(property.enumerable),
+        writable,
+        value,
+        get,
+        set
+    }
+}
+
+/// Returns `true` if the given `descriptor` can be applied to the given
+/// non-configurable `property` without violating its invariants.
+fn is_compatible(property: &Property, descriptor: &PropertyDescriptor<Reference>) -> bool {
+    if descriptor.configurable == Some(true) {
+        return false;
+    }
+    if descriptor.enumerable.map_or(false, |enumerable| enumerable != property.enumerable) {
+        return false;
+    }
+    let is_accessor = descriptor.is_accessor_descriptor();
+    let is_data = descriptor.is_data_descriptor();
+    match property.kind {
+        PropertyKind::Data { ref value, writable } => {
+            !is_accessor && (writable || (descriptor.writable != Some(true) &&
+                                          descriptor.value.as_ref().map_or(true, |new_value| {
+                                              to_slot(new_value) == *value
+                                          })))
+        },
+        PropertyKind::Accessor { ref get, ref set } => {
+            !is_data &&
+            descriptor.get.as_ref().map_or(true, |new_get| to_slot(new_get) == *get) &&
+            descriptor.set.as_ref().map_or(true, |new_set| to_slot(new_set) == *set)
+        }
+    }
+}
+
+/// Applies the given `descriptor` to the given `property`, or creates a new
+/// property from it if `property` is `None`.
+fn apply(property: Option<Property>, descriptor: &PropertyDescriptor<Reference>) -> Property {
+    let slot = |value: &Option<Value<Reference>>| value.as_ref().map(to_slot);
+    let is_accessor = descriptor.is_accessor_descriptor();
+    let (configurable, enumerable, kind) = match property {
+        Some(property) => (property.configurable, property.enumerable, Some(property.kind)),
+        None => (false, false, None)
+    };
+    let kind = match kind {
+        Some(PropertyKind::Accessor { get, set }) if !descriptor.is_data_descriptor() => {
+            PropertyKind::Accessor {
+                get: slot(&descriptor.get).unwrap_or(get),
+                set: slot(&descriptor.set).unwrap_or(set)
+            }
+        },
+        Some(PropertyKind::Data { value, writable }) if !is_accessor => PropertyKind::Data {
+            value: slot(&descriptor.value).unwrap_or(value),
+            writable: descriptor.writable.unwrap_or(writable)
+        },
+        _ if is_accessor => PropertyKind::Accessor {
+            get: slot(&descriptor.get).unwrap_or(Slot::Undefined),
+            set: slot(&descriptor.set).unwrap_or(Slot::Undefined)
+        },
+        _ => PropertyKind::Data {
+            value: slot(&descriptor.value).unwrap_or(Slot::Undefined),
+            writable: descriptor.writable.unwrap_or(false)
+        }
+    };
+    Property {
+        configurable: descriptor.configurable.unwrap_or(configurable),
+        enumerable: descriptor.enumerable.unwrap_or(enumerable),
+        kind
+    }
+}
+
+impl Backend for Reference {
+    type Debugger = Handle;
+    type Environment = Handle;
+    type Frame = Handle;
+    type Object = Handle;
+    type Script = Handle;
+    type Source = Handle;
+    type Symbol = Handle;
+
+    fn debugger_add_debuggee(debugger: &Handle, object: &Object<Reference>) -> Object<Reference> {
+        let mut state = debugger.state.borrow_mut();
+        let global = state.objects[object.handle().index].global;
+        let debuggees = &mut state.debuggers[debugger.index].debuggees;
+        if !debuggees.contains(&global) {
+            debuggees.push(global);
+        }
+        debugger.to(global, Object::from_handle)
+    }
+
+    fn debugger_debugger_statement_handler(debugger: &Handle) -> Option<Rc<Box<dyn DebuggerStatementHandler<Reference>>>> {
+        debugger.state.borrow().debuggers[debugger.index].debugger_statement_handler.clone()
+    }
+
+    fn debugger_enter_frame_handler(debugger: &Handle) -> Option<Rc<Box<dyn EnterFrameHandler<Reference>>>> {
+        debugger.state.borrow().debuggers[debugger.index].enter_frame_handler.clone()
+    }
+
+    fn debugger_exception_unwind_handler(debugger: &Handle) -> Option<Rc<Box<dyn ExceptionUnwindHandler<Reference>>>> {
+        debugger.state.borrow().debuggers[debugger.index].exception_unwind_handler.clone()
+    }
+
+    fn debugger_find_objects(debugger: &Handle, query: &ObjectQuery<Reference>) -> Vec<Object<Reference>> {
+        let state = debugger.state.borrow();
+        let debuggees = &state.debuggers[debugger.index].debuggees;
+        let global = query.global.as_ref().map(|global| state.objects[global.handle().index].global);
+        state.objects.iter().enumerate().filter(|&(_, object)| {
+            debuggees.contains(&object.global) &&
+            global.map_or(true, |global| global == object.global) &&
+            query.class.as_ref().map_or(true, |class| *class == object.class)
+        }).map(|(index, _)| debugger.to(index, Object::from_handle)).collect()
+    }
+
+    fn debugger_find_scripts(debugger: &Handle, query: &ScriptQuery<Reference>) -> Vec<Script<Reference>> {
+        let state = debugger.state.borrow();
+        let debuggees = &state.debuggers[debugger.index].debuggees;
+        let global = query.global.as_ref().map(|global| state.objects[global.handle().index].global);
+        let matches = |index: usize| {
+            let script = &state.scripts[index];
+            debuggees.contains(&script.global) &&
+            global.map_or(true, |global| global == script.global) &&
+            query.url.as_ref().map_or(true, |url| *url == state.sources[script.source].url) &&
+            query.source.as_ref().map_or(true, |source| source.handle().index == script.source) &&
+            query.line.map_or(true, |line| script.spans(line))
+        };
+        let found: Vec<usize> = (0..state.scripts.len()).filter(|&index| matches(index)).collect();
+        found.iter().filter(|&&index| {
+            !query.innermost || !state.scripts[index].children.iter().any(|child| found.contains(child))
+        }).map(|&index| debugger.to(index, Script::from_handle)).collect()
+    }
+
+    fn debugger_find_sources(debugger: &Handle) -> Vec<Source<Reference>> {
+        let state = debugger.state.borrow();
+        let debuggees = &state.debuggers[debugger.index].debuggees;
+        let mut sources: Vec<usize> = state.scripts.iter().filter(|script| {
+            debuggees.contains(&script.global)
+        }).map(|script| script.source).collect();
+        sources.sort();
+        sources.dedup();
+        sources.into_iter().map(|source| debugger.to(source, Source::from_handle)).collect()
+    }
+
+    fn debugger_get_all_breakpoints(debugger: &Handle) -> Vec<Breakpoint<Reference>> {
+        let state = debugger.state.borrow();
+        let debuggees = &state.debuggers[debugger.index].debuggees;
+        let mut found = Vec::new();
+        for (index, script) in state.scripts.iter().enumerate() {
+            if debuggees.contains(&script.global) {
+                for &offset in script.breakpoints.keys() {
+                    found.extend(breakpoints(debugger, &state, index, offset));
+                }
+            }
+        }
+        found.sort_by_key(|breakpoint| breakpoint.id);
+        found
+    }
+
+    fn debugger_get_debuggees(debugger: &Handle) -> Vec<Object<Reference>> {
+        let state = debugger.state.borrow();
+        state.debuggers[debugger.index].debuggees.iter().map(|&global| {
+            debugger.to(global, Object::from_handle)
+        }).collect()
+    }
+
+    fn debugger_get_newest_frame(debugger: &Handle) -> Option<Frame<Reference>> {
+        let state = debugger.state.borrow();
+        let debuggees = &state.debuggers[debugger.index].debuggees;
+        state.stack.iter().rev().find(|&&frame| {
+            debuggees.contains(&state.frames[frame].global)
+        }).map(|&frame| debugger.to(frame, Frame::from_handle))
+    }
+
+    fn debugger_has_debuggee(debugger: &Handle, object: &Object<Reference>) -> bool {
+        let state = debugger.state.borrow();
+        let global = state.objects[object.handle().index].global;
+        state.debuggers[debugger.index].debuggees.contains(&global)
+    }
+
+    fn debugger_is_enabled(debugger: &Handle) -> bool {
+        debugger.state.borrow().debuggers[debugger.index].enabled
+    }
+
+    fn debugger_new_script_handler(debugger: &Handle) -> Option<Rc<Box<dyn NewScriptHandler<Reference>>>> {
+        debugger.state.borrow().debuggers[debugger.index].new_script_handler.clone()
+    }
+
+    fn debugger_remove_debuggee(debugger: &Handle, object: &Object<Reference>) {
+        let mut state = debugger.state.borrow_mut();
+        let global = state.objects[object.handle().index].global;
+        state.debuggers[debugger.index].debuggees.retain(|&debuggee| debuggee != global);
+    }
+
+    fn debugger_set_debugger_statement_handler(debugger: &Handle,
+                                               handler: Option<Rc<Box<dyn DebuggerStatementHandler<Reference>>>>) {
+        debugger.state.borrow_mut().debuggers[debugger.index].debugger_statement_handler = handler;
+    }
+
+    fn debugger_set_enabled(debugger: &Handle, enabled: bool) {
+        debugger.state.borrow_mut().debuggers[debugger.index].enabled = enabled;
+    }
+
+    fn debugger_set_enter_frame_handler(debugger: &Handle, handler: Option<Rc<Box<dyn EnterFrameHandler<Reference>>>>) {
+        debugger.state.borrow_mut().debuggers[debugger.index].enter_frame_handler = handler;
+    }
+
+    fn debugger_set_exception_unwind_handler(debugger: &Handle,
+                                             handler: Option<Rc<Box<dyn ExceptionUnwindHandler<Reference>>>>) {
+        debugger.state.borrow_mut().debuggers[debugger.index].exception_unwind_handler = handler;
+    }
+
+    fn debugger_set_new_script_handler(debugger: &Handle, handler: Option<Rc<Box<dyn NewScriptHandler<Reference>>>>) {
+        debugger.state.borrow_mut().debuggers[debugger.index].new_script_handler = handler;
+    }
+
+    fn environment_callee(environment: &Handle) -> Fallible<Option<Object<Reference>>> {
+        environment.check_environment()?;
+        let callee = environment.state.borrow().environments[environment.index].callee;
+        Ok(callee.map(|callee| environment.to(callee, Object::from_handle)))
+    }
+
+    fn environment_find(environment: &Handle, name: &str) -> Fallible<Option<Environment<Reference>>> {
+        environment.check_environment()?;
+        let found = interpreter::resolve(&environment.state, environment.index, name);
+        Ok(found.map(|found| environment.to(found, Environment::from_handle)))
+    }
+
+    fn environment_get_variable(environment: &Handle, name: &str) -> Fallible<Value<Reference>> {
+        environment.check_environment()?;
+        environment.check_environment_proxy()?;
+        let state = environment.state.borrow();
+        let data = &state.environments[environment.index];
+        match data.object {
+            Some(object) => {
+                let found = interpreter::find_property(&environment.state, object, &Key::from_name(name));
+                match found {
+                    Some((_, Property { kind: PropertyKind::Data { value, .. }, .. })) => {
+                        Ok(environment.value(&value))
+                    },
+                    Some(_) => Err(Error::DebuggeeWouldRun),
+                    None => Ok(Value::Undefined)
+                }
+            },
+            None => Ok(data.bindings.iter().find(|binding| binding.0 == name).map_or(Value::Undefined, |binding| {
+                environment.value(&binding.1)
+            }))
+        }
+    }
+
+    fn environment_is_inspectable(environment: &Handle) -> bool {
+        environment.check_environment().is_ok()
+    }
+
+    fn environment_names(environment: &Handle) -> Fallible<Vec<String>> {
+        environment.check_environment()?;
+        environment.check_environment_proxy()?;
+        let object = environment.state.borrow().environments[environment.index].object;
+        Ok(match object {
+            Some(object) => {
+                let state = environment.state.borrow();
+                state.objects[object].own_keys().iter().filter_map(Key::name).collect()
+            },
+            None => {
+                let state = environment.state.borrow();
+                state.environments[environment.index].bindings.iter().map(|binding| {
+                    binding.0.clone()
+                }).collect()
+            }
+        })
+    }
+
+    fn environment_object(environment: &Handle) -> Fallible<Option<Object<Reference>>> {
+        environment.check_environment()?;
+        let object = environment.state.borrow().environments[environment.index].object;
+        Ok(object.map(|object| environment.to(object, Object::from_handle)))
+    }
+
+    fn environment_is_optimized_out(environment: &Handle) -> Fallible<bool> {
+        environment.check_environment()?;
+        Ok(environment.state.borrow().environments[environment.index].optimized_out)
+    }
+
+    fn environment_parent(environment: &Handle) -> Fallible<Option<Environment<Reference>>> {
+        environment.check_environment()?;
+        let parent = environment.state.borrow().environments[environment.index].parent;
+        Ok(parent.map(|parent| environment.to(parent, Environment::from_handle)))
+    }
+
+    fn environment_set_variable(environment: &Handle, name: &str, value: &Value<Reference>) -> Fallible<()> {
+        environment.check_environment()?;
+        environment.check_environment_proxy()?;
+        let mut state = environment.state.borrow_mut();
+        match state.environments[environment.index].object {
+            Some(object) => match state.objects[object].property_mut(&Key::from_name(name)) {
+                Some(&mut Property { kind: PropertyKind::Data { value: ref mut old_value, writable: true }, .. }) => {
+                    *old_value = to_slot(value);
+                    Ok(())
+                },
+                Some(&mut Property { kind: PropertyKind::Data { .. }, .. }) => Ok(()),
+                Some(_) => Err(Error::DebuggeeWouldRun),
+                None => Err(Error::VariableNotFound)
+            },
+            None => {
+                let bindings = &mut state.environments[environment.index].bindings;
+                match bindings.iter_mut().find(|binding| binding.0 == name) {
+                    Some(binding) => {
+                        binding.1 = to_slot(value);
+                        Ok(())
+                    },
+                    None => Err(Error::VariableNotFound)
+                }
+            }
+        }
+    }
+
+    fn environment_get_type(environment: &Handle) -> Fallible<EnvironmentType> {
+        environment.check_environment()?;
+        Ok(environment.state.borrow().environments[environment.index].kind)
+    }
+
+    fn frame_arguments(frame: &Handle) -> Option<Vec<Value<Reference>>> {
+        let state = frame.state.borrow();
+        let data = &state.frames[frame.index];
+        if data.kind == FrameType::Call {
+            Some(data.arguments.iter().map(|argument| frame.value(argument)).collect())
+        } else {
+            None
+        }
+    }
+
+    fn frame_callee(frame: &Handle) -> Option<Object<Reference>> {
+        let callee = frame.state.borrow().frames[frame.index].callee;
+        callee.map(|callee| frame.to(callee, Object::from_handle))
+    }
+
+    fn frame_depth(frame: &Handle) -> u32 {
+        frame.state.borrow().frames[frame.index].depth
+    }
+
+    fn frame_is_constructing(frame: &Handle) -> bool {
+        frame.state.borrow().frames[frame.index].constructing
+    }
+
+    fn frame_environment(frame: &Handle) -> Option<Environment<Reference>> {
+        if !frame.is_debuggee_frame() {
+            return None;
+        }
+        let environment = frame.state.borrow().frames[frame.index].environment;
+        Some(frame.to(environment, Environment::from_handle))
+    }
+
+    fn frame_eval(frame: &Handle, code: &str) -> Fallible<CompletionValue<Reference>> {
+        Reference::frame_eval_with_bindings(frame, code, &BTreeMap::new())
+    }
+
+    fn frame_eval_with_bindings(frame: &Handle, code: &str,
+                                bindings: &BTreeMap<String, Value<Reference>>) -> Fallible<CompletionValue<Reference>> {
+        if !frame.is_debuggee_frame() {
+            return Err(Error::FrameNotDebuggee);
+        }
+        let (environment, this) = {
+            let state = frame.state.borrow();
+            let data = &state.frames[frame.index];
+            (data.environment, data.this.clone())
+        };
+        let bindings = bindings.iter().map(|(name, value)| (name.clone(), to_slot(value))).collect();
+        let completion = interpreter::evaluate_code(&frame.state, environment, &this, code, &bindings);
+        Ok(frame.completion_value(&completion))
+    }
+
+    fn frame_get_type(frame: &Handle) -> FrameType {
+        frame.state.borrow().frames[frame.index].kind
+    }
+
+    fn frame_implementation(_frame: &Handle) -> FrameImplementation {
+        FrameImplementation::Interpreter
+    }
+
+    fn frame_is_live(frame: &Handle) -> bool {
+        frame.state.borrow().frames[frame.index].live
+    }
+
+    fn frame_is_suspended(frame: &Handle) -> bool {
+        frame.state.borrow().frames[frame.index].suspended
+    }
+
+    fn frame_offset(frame: &Handle) -> Option<u32> {
+        if frame.is_debuggee_frame() {
+            Some(frame.state.borrow().frames[frame.index].offset)
+        } else {
+            None
+        }
+    }
+
+    fn frame_older(frame: &Handle) -> Option<Frame<Reference>> {
+        let state = frame.state.borrow();
+        let mut older = state.frames[frame.index].older;
+        while let Some(index) = older {
+            if state.is_debuggee(state.frames[index].global) {
+                return Some(frame.to(index, Frame::from_handle));
+            }
+            older = state.frames[index].older;
+        }
+        None
+    }
+
+    fn frame_pop_handler(frame: &Handle) -> Option<Rc<Box<dyn PopHandler<Reference>>>> {
+        frame.state.borrow().frames[frame.index].pop_handler.clone()
+    }
+
+    fn frame_step_handler(frame: &Handle) -> Option<Rc<Box<dyn StepHandler<Reference>>>> {
+        frame.state.borrow().frames[frame.index].step_handler.clone()
+    }
+
+    fn frame_script(frame: &Handle) -> Option<Script<Reference>> {
+        if !frame.is_debuggee_frame() {
+            return None;
+        }
+        let script = frame.state.borrow().frames[frame.index].script;
+        Some(frame.to(script, Script::from_handle))
+    }
+
+    fn frame_set_pop_handler(frame: &Handle, handler: Option<Rc<Box<dyn PopHandler<Reference>>>>) {
+        frame.state.borrow_mut().frames[frame.index].pop_handler = handler;
+    }
+
+    fn frame_set_step_handler(frame: &Handle, handler: Option<Rc<Box<dyn StepHandler<Reference>>>>) {
+        frame.state.borrow_mut().frames[frame.index].step_handler = handler;
+    }
+
+    fn frame_this(frame: &Handle) -> Value<Reference> {
+        let this = frame.state.borrow().frames[frame.index].this.clone();
+        frame.value(&this)
+    }
+
+    fn memory_drain_allocations_log(debugger: &Handle) -> Vec<AllocationLogEntry> {
+        let mut state = debugger.state.borrow_mut();
+        state.debuggers[debugger.index].allocations_log.drain(..).collect()
+    }
+
+    fn memory_is_recording_allocations(debugger: &Handle) -> bool {
+        debugger.state.borrow().debuggers[debugger.index].recording_allocations
+    }
+
+    fn memory_set_recording_allocations(debugger: &Handle, recording: bool) {
+        debugger.state.borrow_mut().debuggers[debugger.index].recording_allocations = recording;
+    }
+
+    fn memory_take_census(debugger: &Handle) -> BTreeMap<String, ClassCensus> {
+        let state = debugger.state.borrow();
+        let debuggees = &state.debuggers[debugger.index].debuggees;
+        let mut census: BTreeMap<String, ClassCensus> = BTreeMap::new();
+        for object in state.objects.iter().filter(|object| debuggees.contains(&object.global)) {
+            let entry = census.entry(object.class.clone()).or_default();
+            entry.count += 1;
+            entry.bytes += object.size();
+        }
+        census
+    }
+
+    fn object_allocation_site(object: &Handle) -> Option<Vec<SavedFrame>> {
+        object.state.borrow().objects[object.index].allocation_site.clone()
+    }
+
+    fn object_as_environment(object: &Handle) -> Fallible<Environment<Reference>> {
+        let environment = object.check_global()?;
+        Ok(object.to(environment, Environment::from_handle))
+    }
+
+    fn object_bound_arguments(object: &Handle) -> Option<Vec<Value<Reference>>> {
+        let state = object.state.borrow();
+        let bound = state.objects[object.index].function.as_ref().and_then(|function| function.bound.as_ref());
+        bound.map(|bound| bound.arguments.iter().map(|argument| object.value(argument)).collect())
+    }
+
+    fn object_bound_target_function(object: &Handle) -> Option<Object<Reference>> {
+        let state = object.state.borrow();
+        let bound = state.objects[object.index].function.as_ref().and_then(|function| function.bound.as_ref());
+        bound.map(|bound| object.to(bound.target, Object::from_handle))
+    }
+
+    fn object_bound_this(object: &Handle) -> Value<Reference> {
+        let state = object.state.borrow();
+        let bound = state.objects[object.index].function.as_ref().and_then(|function| function.bound.as_ref());
+        bound.map_or(Value::Undefined, |bound| object.value(&bound.this))
+    }
+
+    fn object_buffer(object: &Handle) -> Option<Object<Reference>> {
+        let state = object.state.borrow();
+        state.objects[object.index].view.as_ref().map(|view| object.to(view.buffer, Object::from_handle))
+    }
+
+    fn object_byte_length(object: &Handle) -> Option<usize> {
+        let state = object.state.borrow();
+        state.binary(object.index).map(|(_, range)| range.len())
+    }
+
+    fn object_byte_offset(object: &Handle) -> Option<usize> {
+        let state = object.state.borrow();
+        if state.objects[object.index].view.is_some() {
+            state.binary(object.index).map(|(_, range)| range.start)
+        } else {
+            None
+        }
+    }
+
+    fn object_call(object: &Handle, this: &Value<Reference>,
+                   arguments: &[Value<Reference>]) -> Fallible<CompletionValue<Reference>> {
+        if !Reference::object_is_callable(object) {
+            return Err(Error::ObjectNotCallable);
+        }
+        let arguments = arguments.iter().map(to_slot).collect();
+        let completion = interpreter::call(&object.state, object.index, to_slot(this), arguments, false);
+        Ok(object.completion_value(&completion))
+    }
+
+    fn object_construct(object: &Handle, arguments: &[Value<Reference>]) -> Fallible<CompletionValue<Reference>> {
+        if !Reference::object_is_callable(object) {
+            return Err(Error::ObjectNotCallable);
+        }
+        let arguments = arguments.iter().map(to_slot).collect();
+        let completion = interpreter::construct(&object.state, object.index, arguments);
+        Ok(object.completion_value(&completion))
+    }
+
+    fn object_class(object: &Handle) -> String {
+        object.state.borrow().objects[object.index].class.clone()
+    }
+
+    fn object_collection_size(object: &Handle) -> Option<usize> {
+        let state = object.state.borrow();
+        state.objects[object.index].collection.as_ref().map(CollectionData::size)
+    }
+
+    fn object_display_name(object: &Handle) -> Option<String> {
+        let state = object.state.borrow();
+        state.objects[object.index].function.as_ref().and_then(|function| function.display_name.clone())
+    }
+
+    fn object_define_property(object: &Handle, key: &PropertyKey<Reference>,
+                              descriptor: &PropertyDescriptor<Reference>) -> Fallible<()> {
+        object.check_proxy()?;
+        let key = to_key(key);
+        let mut state = object.state.borrow_mut();
+        let data = &mut state.objects[object.index];
+        match data.property_mut(&key) {
+            Some(property) => {
+                if !property.configurable && !is_compatible(property, descriptor) {
+                    return Err(Error::PropertyNotConfigurable);
+                }
+                *property = apply(Some(property.clone()), descriptor);
+                return Ok(());
+            },
+            None => if !data.extensible {
+                return Err(Error::ObjectNotExtensible);
+            }
+        }
+        data.properties.push((key, apply(None, descriptor)));
+        Ok(())
+    }
+
+    fn object_delete_property(object: &Handle, key: &PropertyKey<Reference>) -> Fallible<()> {
+        object.check_proxy()?;
+        let key = to_key(key);
+        let mut state = object.state.borrow_mut();
+        let properties = &mut state.objects[object.index].properties;
+        match properties.iter().position(|(other, _)| *other == key) {
+            Some(index) if !properties[index].1.configurable => Err(Error::PropertyNotConfigurable),
+            Some(index) => {
+                properties.remove(index);
+                Ok(())
+            },
+            None => Ok(())
+        }
+    }
+
+    fn object_element_type(object: &Handle) -> Option<ElementType> {
+        let state = object.state.borrow();
+        state.objects[object.index].view.as_ref().and_then(|view| view.element_type)
+    }
+
+    fn object_environment(object: &Handle) -> Option<Environment<Reference>> {
+        let state = object.state.borrow();
+        let environment = state.objects[object.index].function.as_ref().and_then(|function| function.environment);
+        environment.map(|environment| object.to(environment, Environment::from_handle))
+    }
+
+    fn object_error_location(object: &Handle) -> Option<SourceLocation> {
+        let state = object.state.borrow();
+        let stack = state.objects[object.index].error.as_ref().map(|error| &error.stack);
+        stack.and_then(|stack| stack.first()).map(|frame| SourceLocation {
+            url: frame.url.clone(),
+            line: frame.line,
+            column: frame.column
+        })
+    }
+
+    fn object_error_message(object: &Handle) -> Option<String> {
+        let state = object.state.borrow();
+        state.objects[object.index].error.as_ref().map(|error| error.message.clone())
+    }
+
+    fn object_error_name(object: &Handle) -> Option<String> {
+        let state = object.state.borrow();
+        state.objects[object.index].error.as_ref().map(|error| error.name.clone())
+    }
+
+    fn object_error_stack(object: &Handle) -> Option<Vec<SavedFrame>> {
+        let state = object.state.borrow();
+        state.objects[object.index].error.as_ref().map(|error| error.stack.clone())
+    }
+
+    fn object_execute_in_global(object: &Handle, code: &str) -> Fallible<CompletionValue<Reference>> {
+        Reference::object_execute_in_global_with_bindings(object, code, &BTreeMap::new())
+    }
+
+    fn object_execute_in_global_with_bindings(object: &Handle, code: &str,
+                                              bindings: &BTreeMap<String, Value<Reference>>) -> Fallible<CompletionValue<Reference>> {
+        let environment = object.check_global()?;
+        let bindings = bindings.iter().map(|(name, value)| (name.clone(), to_slot(value))).collect();
+        let this = Slot::Object(object.index);
+        let completion = interpreter::evaluate_code(&object.state, environment, &this, code, &bindings);
+        Ok(object.completion_value(&completion))
+    }
+
+    fn object_freeze(object: &Handle) -> Fallible<()> {
+        object.check_proxy()?;
+        object.set_integrity(true);
+        Ok(())
+    }
+
+    fn object_function_kind(object: &Handle) -> Option<FunctionKind> {
+        let state = object.state.borrow();
+        let mut function = state.objects[object.index].function.as_ref()?;
+        while let Some(ref bound) = function.bound {
+            function = state.objects[bound.target].function.as_ref()?;
+        }
+        Some(function.kind)
+    }
+
+    fn object_get_own_property_descriptor(object: &Handle, key: &PropertyKey<Reference>) -> Fallible<Option<PropertyDescriptor<Reference>>> {
+        object.check_proxy()?;
+        let state = object.state.borrow();
+        Ok(state.objects[object.index].property(&to_key(key)).map(|property| to_descriptor(object, property)))
+    }
+
+    fn object_get_own_property_names(object: &Handle) -> Fallible<Vec<PropertyKey<Reference>>> {
+        object.check_proxy()?;
+        let state = object.state.borrow();
+        Ok(state.objects[object.index].own_keys().iter().filter(|key| {
+            !matches!(**key, Key::Symbol(_))
+        }).map(|key| object.property_key(key)).collect())
+    }
+
+    fn object_get_own_property_symbols(object: &Handle) -> Fallible<Vec<Symbol<Reference>>> {
+        object.check_proxy()?;
+        let state = object.state.borrow();
+        Ok(state.objects[object.index].own_keys().iter().filter_map(|key| match *key {
+            Key::Symbol(symbol) => Some(object.to(symbol, Symbol::from_handle)),
+            _ => None
+        }).collect())
+    }
+
+    fn object_get_private_fields(object: &Handle) -> Vec<(String, Value<Reference>)> {
+        let state = object.state.borrow();
+        state.objects[object.index].private_fields.iter().map(|(name, value)| {
+            (name.clone(), object.value(value))
+        }).collect()
+    }
+
+    fn object_get_private_methods(object: &Handle) -> Vec<(String, PropertyDescriptor<Reference>)> {
+        let state = object.state.borrow();
+        state.objects[object.index].private_methods.iter().map(|(name, property)| {
+            (name.clone(), to_descriptor(object, property))
+        }).collect()
+    }
+
+    fn object_get_prototype_of(object: &Handle) -> Option<Object<Reference>> {
+        let prototype = object.state.borrow().objects[object.index].prototype;
+        prototype.map(|prototype| object.to(prototype, Object::from_handle))
+    }
+
+    fn object_global(object: &Handle) -> Object<Reference> {
+        let global = object.state.borrow().objects[object.index].global;
+        object.to(global, Object::from_handle)
+    }
+
+    fn object_is_arrow_function(object: &Handle) -> bool {
+        let state = object.state.borrow();
+        state.objects[object.index].function.as_ref().map_or(false, |function| {
+            function.kind == FunctionKind::Arrow || function.kind == FunctionKind::AsyncArrow
+        })
+    }
+
+    fn object_is_bound_function(object: &Handle) -> bool {
+        let state = object.state.borrow();
+        state.objects[object.index].function.as_ref().map_or(false, |function| function.bound.is_some())
+    }
+
+    fn object_is_callable(object: &Handle) -> bool {
+        object.state.borrow().objects[object.index].function.is_some()
+    }
+
+    fn object_is_detached(object: &Handle) -> bool {
+        let state = object.state.borrow();
+        let data = &state.objects[object.index];
+        let buffer = data.view.as_ref().map_or(object.index, |view| view.buffer);
+        state.objects[buffer].buffer.as_ref().map_or(false, |buffer| buffer.bytes.is_none())
+    }
+
+    fn object_is_extensible(object: &Handle) -> Fallible<bool> {
+        object.check_proxy()?;
+        Ok(object.state.borrow().objects[object.index].extensible)
+    }
+
+    fn object_is_frozen(object: &Handle) -> Fallible<bool> {
+        object.check_proxy()?;
+        Ok(object.has_integrity(true))
+    }
+
+    fn object_is_native(object: &Handle) -> bool {
+        let state = object.state.borrow();
+        state.objects[object.index].function.as_ref().map_or(false, |function| {
+            function.script.is_none() && function.bound.is_none()
+        })
+    }
+
+    fn object_is_proxy(object: &Handle) -> bool {
+        object.state.borrow().objects[object.index].proxy.is_some()
+    }
+
+    fn object_is_revoked_proxy(object: &Handle) -> bool {
+        let state = object.state.borrow();
+        state.objects[object.index].proxy.as_ref().map_or(false, |proxy| proxy.revoked)
+    }
+
+    fn object_is_sealed(object: &Handle) -> Fallible<bool> {
+        object.check_proxy()?;
+        Ok(object.has_integrity(false))
+    }
+
+    fn object_map_entries(object: &Handle, range: Range<usize>) -> Option<Vec<(Value<Reference>, Value<Reference>)>> {
+        let state = object.state.borrow();
+        match state.objects[object.index].collection {
+            Some(CollectionData::Map(ref entries)) => {
+                Some(page(entries, range).iter().map(|(key, value)| (object.value(key), object.value(value))).collect())
+            }
+            _ => None
+        }
+    }
+
+    fn object_name(object: &Handle) -> Option<String> {
+        let state = object.state.borrow();
+        state.objects[object.index].function.as_ref().and_then(|function| function.name.clone())
+    }
+
+    fn object_parameter_names(object: &Handle) -> Option<Vec<String>> {
+        let state = object.state.borrow();
+        let function = state.objects[object.index].function.as_ref();
+        function.and_then(|function| function.script.map(|_| function.parameters.clone()))
+    }
+
+    fn object_prevent_extensions(object: &Handle) -> Fallible<()> {
+        object.check_proxy()?;
+        object.state.borrow_mut().objects[object.index].extensible = false;
+        Ok(())
+    }
+
+    fn object_promise_allocation_site(object: &Handle) -> Option<Vec<SavedFrame>> {
+        let state = object.state.borrow();
+        state.objects[object.index].promise.as_ref().and_then(|promise| promise.allocation_site.clone())
+    }
+
+    fn object_promise_dependent_promises(object: &Handle) -> Option<Vec<Object<Reference>>> {
+        let state = object.state.borrow();
+        state.objects[object.index].promise.as_ref().map(|promise| {
+            promise.dependents.iter().map(|&dependent| object.to(dependent, Object::from_handle)).collect()
+        })
+    }
+
+    fn object_promise_reason(object: &Handle) -> Option<Value<Reference>> {
+        let state = object.state.borrow();
+        match state.objects[object.index].promise {
+            Some(ref promise) if promise.state == PromiseState::Rejected => Some(object.value(&promise.result)),
+            _ => None
+        }
+    }
+
+    fn object_promise_resolution_site(object: &Handle) -> Option<Vec<SavedFrame>> {
+        let state = object.state.borrow();
+        state.objects[object.index].promise.as_ref().and_then(|promise| promise.resolution_site.clone())
+    }
+
+    fn object_promise_state(object: &Handle) -> Option<PromiseState> {
+        let state = object.state.borrow();
+        state.objects[object.index].promise.as_ref().map(|promise| promise.state)
+    }
+
+    fn object_promise_value(object: &Handle) -> Option<Value<Reference>> {
+        let state = object.state.borrow();
+        match state.objects[object.index].promise {
+            Some(ref promise) if promise.state == PromiseState::Fulfilled => Some(object.value(&promise.result)),
+            _ => None
+        }
+    }
+
+    fn object_proxy_handler(object: &Handle) -> Option<Object<Reference>> {
+        object.proxy(|proxy| proxy.handler)
+    }
+
+    fn object_proxy_target(object: &Handle) -> Option<Object<Reference>> {
+        object.proxy(|proxy| proxy.target)
+    }
+
+    fn object_read_bytes(object: &Handle, range: Range<usize>) -> Option<Vec<u8>> {
+        let state = object.state.borrow();
+        let (buffer, covered) = state.binary(object.index)?;
+        let bytes = state.objects[buffer].buffer.as_ref().and_then(|buffer| buffer.bytes.as_ref());
+        Some(bytes.map_or(Vec::new(), |bytes| page(&bytes[covered], range).to_vec()))
+    }
+
+    fn object_seal(object: &Handle) -> Fallible<()> {
+        object.check_proxy()?;
+        object.set_integrity(false);
+        Ok(())
+    }
+
+    fn object_script(object: &Handle) -> Option<Script<Reference>> {
+        let state = object.state.borrow();
+        let script = state.objects[object.index].function.as_ref().and_then(|function| function.script);
+        script.map(|script| object.to(script, Script::from_handle))
+    }
+
+    fn object_set_entries(object: &Handle, range: Range<usize>) -> Option<Vec<Value<Reference>>> {
+        let state = object.state.borrow();
+        match state.objects[object.index].collection {
+            Some(CollectionData::Set(ref values)) => Some(page(values, range).iter().map(|value| object.value(value)).collect()),
+            _ => None
+        }
+    }
+
+    fn object_set_private_field(object: &Handle, name: &str, value: &Value<Reference>) -> Fallible<()> {
+        let mut state = object.state.borrow_mut();
+        let fields = &mut state.objects[object.index].private_fields;
+        let field = fields.iter_mut().find(|(other, _)| other == name).ok_or(Error::FieldNotFound)?;
+        field.1 = to_slot(value);
+        Ok(())
+    }
+
+    fn object_weak_entries(object: &Handle, range: Range<usize>) -> Option<Vec<WeakEntry<Reference>>> {
+        let state = object.state.borrow();
+        match state.objects[object.index].collection {
+            Some(CollectionData::WeakMap(ref entries)) => Some(page(entries, range).iter().map(|&(key, ref value)| {
+                (object.to(key, Object::from_handle), Some(object.value(value)))
+            }).collect()),
+            Some(CollectionData::WeakSet(ref keys)) => {
+                Some(page(keys, range).iter().map(|&key| (object.to(key, Object::from_handle), None)).collect())
+            }
+            _ => None
+        }
+    }
+
+    fn object_write_bytes(object: &Handle, offset: usize, bytes: &[u8]) -> Fallible<()> {
+        let mut state = object.state.borrow_mut();
+        let (buffer, covered) = state.binary(object.index).ok_or(Error::ObjectNotBinaryData)?;
+        let data = state.objects[buffer].buffer.as_mut().and_then(|buffer| buffer.bytes.as_mut());
+        let data = data.ok_or(Error::BufferDetached)?;
+        match offset.checked_add(bytes.len()) {
+            Some(end) if end <= covered.len() => {
+                data[covered.start + offset..covered.start + end].copy_from_slice(bytes);
+                Ok(())
+            }
+            _ => Err(Error::RangeNotValid)
+        }
+    }
+
+    fn script_clear_all_breakpoints(script: &Handle) {
+        script.state.borrow_mut().scripts[script.index].breakpoints.clear();
+    }
+
+    fn script_clear_breakpoints(script: &Handle, offset: u32) -> Fallible<()> {
+        let mut state = script.state.borrow_mut();
+        let data = &mut state.scripts[script.index];
+        if offset as usize >= data.instructions.len() {
+            return Err(Error::OffsetNotValid);
+        }
+        data.breakpoints.remove(&offset);
+        Ok(())
+    }
+
+    fn script_display_name(script: &Handle) -> Option<String> {
+        script.state.borrow().scripts[script.index].display_name.clone()
+    }
+
+    fn script_get_all_line_offsets(script: &Handle) -> BTreeMap<u32, Vec<u32>> {
+        let state = script.state.borrow();
+        let data = &state.scripts[script.index];
+        let mut offsets = BTreeMap::new();
+        for (offset, instruction) in data.instructions.iter().enumerate() {
+            if data.is_entry_point(offset) {
+                offsets.entry(instruction.line).or_insert_with(Vec::new).push(offset as u32);
+            }
+        }
+        offsets
+    }
+
+    fn script_get_breakpoints(script: &Handle, offset: u32) -> Fallible<Vec<Breakpoint<Reference>>> {
+        let state = script.state.borrow();
+        if offset as usize >= state.scripts[script.index].instructions.len() {
+            return Err(Error::OffsetNotValid);
+        }
+        Ok(breakpoints(script, &state, script.index, offset))
+    }
+
+    fn script_get_child_scripts(script: &Handle) -> Vec<Script<Reference>> {
+        let state = script.state.borrow();
+        state.scripts[script.index].children.iter().map(|&child| {
+            script.to(child, Script::from_handle)
+        }).collect()
+    }
+
+    fn script_get_line_offsets(script: &Handle, line: u32) -> Vec<u32> {
+        Reference::script_get_all_line_offsets(script).remove(&line).unwrap_or_default()
+    }
+
+    fn script_get_offset_location(script: &Handle, offset: u32) -> Fallible<SourceLocation> {
+        let state = script.state.borrow();
+        let data = &state.scripts[script.index];
+        let instruction = data.instructions.get(offset as usize).ok_or(Error::OffsetNotValid)?;
+        Ok(SourceLocation {
+            url: state.sources[data.source].url.clone(),
+            line: instruction.line,
+            column: instruction.column
+        })
+    }
+
+    fn script_global(script: &Handle) -> Object<Reference> {
+        let global = script.state.borrow().scripts[script.index].global;
+        script.to(global, Object::from_handle)
+    }
+
+    fn script_line_count(script: &Handle) -> u32 {
+        script.state.borrow().scripts[script.index].line_count()
+    }
+
+    fn script_remove_breakpoint(script: &Handle, id: BreakpointId) -> Fallible<()> {
+        let mut state = script.state.borrow_mut();
+        let data = &mut state.scripts[script.index];
+        let offset = data.breakpoints.iter().find(|(_, breakpoints)| {
+            breakpoints.iter().any(|breakpoint| breakpoint.id == id.0)
+        }).map(|(&offset, _)| offset).ok_or(Error::BreakpointNotFound)?;
+        let breakpoints = data.breakpoints.get_mut(&offset).unwrap();
+        breakpoints.retain(|breakpoint| breakpoint.id != id.0);
+        if breakpoints.is_empty() {
+            data.breakpoints.remove(&offset);
+        }
+        Ok(())
+    }
+
+    fn script_set_breakpoint(script: &Handle, offset: u32,
+                             handler: Rc<Box<dyn BreakpointHandler<Reference>>>) -> Fallible<BreakpointId> {
+        let mut state = script.state.borrow_mut();
+        if offset as usize >= state.scripts[script.index].instructions.len() {
+            return Err(Error::OffsetNotValid);
+        }
+        let id = state.breakpoints;
+        state.breakpoints += 1;
+        state.scripts[script.index].breakpoints.entry(offset).or_default().push(BreakpointData {
+            id,
+            enabled: true,
+            handler
+        });
+        Ok(BreakpointId(id))
+    }
+
+    fn script_set_breakpoint_enabled(script: &Handle, id: BreakpointId, enabled: bool) -> Fallible<()> {
+        let mut state = script.state.borrow_mut();
+        let breakpoint = state.scripts[script.index].breakpoints.values_mut().flat_map(|breakpoints| {
+            breakpoints.iter_mut()
+        }).find(|breakpoint| breakpoint.id == id.0).ok_or(Error::BreakpointNotFound)?;
+        breakpoint.enabled = enabled;
+        Ok(())
+    }
+
+    fn script_source(script: &Handle) -> Option<Source<Reference>> {
+        let source = script.state.borrow().scripts[script.index].source;
+        Some(script.to(source, Source::from_handle))
+    }
+
+    fn script_source_length(script: &Handle) -> u32 {
+        script.state.borrow().scripts[script.index].source_length
+    }
+
+    fn script_source_start(script: &Handle) -> u32 {
+        script.state.borrow().scripts[script.index].source_start
+    }
+
+    fn script_start_line(script: &Handle) -> u32 {
+        script.state.borrow().scripts[script.index].start_line
+    }
+
+    fn script_url(script: &Handle) -> String {
+        let state = script.state.borrow();
+        state.sources[state.scripts[script.index].source].url.clone()
+    }
+
+    fn source_canonical_id(source: &Handle) -> String {
+        format!("source{}", source.index)
+    }
+
+    fn source_element(source: &Handle) -> Option<Object<Reference>> {
+        let element = source.state.borrow().sources[source.index].element;
+        element.map(|element| source.to(element, Object::from_handle))
+    }
+
+    fn source_element_attribute_name(source: &Handle) -> Option<String> {
+        source.state.borrow().sources[source.index].element_attribute_name.clone()
+    }
+
+    fn source_introduction_offset(source: &Handle) -> Option<u32> {
+        source.state.borrow().sources[source.index].introduction_offset
+    }
+
+    fn source_introduction_script(source: &Handle) -> Option<Script<Reference>> {
+        let script = source.state.borrow().sources[source.index].introduction_script;
+        script.map(|script| source.to(script, Script::from_handle))
+    }
+
+    fn source_introduction_type(source: &Handle) -> Option<IntroductionType> {
+        source.state.borrow().sources[source.index].introduction_type
+    }
+
+    fn source_source_map_url(source: &Handle) -> Option<String> {
+        source.state.borrow().sources[source.index].source_map_url.clone()
+    }
+
+    fn source_text(source: &Handle) -> String {
+        source.state.borrow().sources[source.index].text.clone()
+    }
+
+    fn source_url(source: &Handle) -> String {
+        source.state.borrow().sources[source.index].url.clone()
+    }
+
+    fn symbol_description(symbol: &Handle) -> Option<String> {
+        symbol.state.borrow().symbols[symbol.index].description.clone()
+    }
+
+    fn symbol_registry_key(symbol: &Handle) -> Option<String> {
+        symbol.state.borrow().symbols[symbol.index].registry_key.clone()
+    }
+}
@@ -0,0 +1,778 @@
+//! An interpreter for the scripts of a simulated debuggee.
+//!
+//! The interpreter executes scripts one instruction at a time, and calls the
+//! step, breakpoint and pop handlers of the frames and scripts involved as it
+//! goes.
+
+use std::collections::BTreeMap;
+
+use {BigInt, EnvironmentType, Frame, FrameType, FunctionKind, PromiseState, ResumptionValue};
+
+use super::{Handle, Reference, Shared, to_completion, to_completion_value, to_value};
+use super::parser::{self, BinaryOperator, Expression, UnaryOperator};
+use super::state::{EnvironmentData, FrameData, FunctionData, Key, ObjectData, Operation, Property,
+                   PropertyKind, Slot};
+
+/// A completion as produced by the interpreter. This mirrors
+/// `CompletionValue`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Completion {
+    Return(Slot),
+    Throw(Slot),
+    Terminate,
+    Yield(Slot),
+    Await(Slot)
+}
+
+/// The result of evaluating an expression. An expression either produces a
+/// value, or completes abruptly, in which case the completion is never a
+/// `Return`.
+pub type Evaluation = Result<Slot, Completion>;
+
+fn evaluation(completion: Completion) -> Evaluation {
+    match completion {
+        Completion::Return(value) => Ok(value),
+        completion => Err(completion)
+    }
+}
+
+/// Returns a completion that throws an error with the given `name` and
+/// `message`. Errors are represented as strings.
+pub fn error(name: &str, message: &str) -> Completion {
+    Completion::Throw(Slot::String(format!("{}: {}", name, message)))
+}
+
+/// Executes the given top-level `script` in a new global frame.
+pub fn execute(state: &Shared, script: usize) -> Completion {
+    let (global, environment) = {
+        let state = state.borrow();
+        let global = state.scripts[script].global;
+        (global, state.objects[global].environment.unwrap())
+    };
+    let frame = add_frame(state, FrameType::Global, global, script, environment, None,
+                          Slot::Object(global), Vec::new(), false);
+    push(state, frame);
+    run(state, frame)
+}
+
+/// Calls the given `callee` with the given `this` value and `arguments`. If
+/// `constructing` is `true`, the callee is called as a constructor.
+pub fn call(state: &Shared, callee: usize, this: Slot, arguments: Vec<Slot>,
+            constructing: bool) -> Completion {
+    let function = match state.borrow().objects[callee].function.clone() {
+        Some(function) => function,
+        None => return error("TypeError", "not a function")
+    };
+    let constructor = function.kind == FunctionKind::Normal || function.kind == FunctionKind::ClassConstructor;
+    if constructing && !constructor {
+        return error("TypeError", "not a constructor");
+    }
+    if function.kind == FunctionKind::ClassConstructor && !constructing {
+        return error("TypeError", "class constructors must be invoked with 'new'");
+    }
+    if let Some(bound) = function.bound {
+        let mut all_arguments = bound.arguments;
+        all_arguments.extend(arguments);
+        let this = if constructing { this } else { bound.this };
+        return call(state, bound.target, this, all_arguments, constructing);
+    }
+    let script = match function.script {
+        Some(script) => script,
+        None => return Completion::Return(Slot::Undefined)
+    };
+    let global = state.borrow().scripts[script].global;
+    let bindings = function.parameters.iter().enumerate().map(|(index, name)| {
+        (name.clone(), arguments.get(index).cloned().unwrap_or(Slot::Undefined))
+    }).collect();
+    let environment = state.borrow_mut().add_environment(EnvironmentData {
+        kind: EnvironmentType::Declarative,
+        global,
+        parent: function.environment,
+        bindings,
+        object: None,
+        callee: Some(callee),
+        optimized_out: false
+    });
+    let frame = add_frame(state, FrameType::Call, global, script, environment, Some(callee),
+                          this, arguments, constructing);
+    match function.kind {
+        FunctionKind::Generator | FunctionKind::AsyncGenerator => {
+            let mut state = state.borrow_mut();
+            state.frames[frame].suspended = true;
+            let mut generator = ObjectData::new("Generator", global);
+            generator.frame = Some(frame);
+            Completion::Return(Slot::Object(state.add_object(generator)))
+        },
+        FunctionKind::Async | FunctionKind::AsyncArrow => {
+            let promise = {
+                let mut state = state.borrow_mut();
+                let promise = state.add_promise(global);
+                state.objects[promise].frame = Some(frame);
+                state.frames[frame].promise = Some(promise);
+                promise
+            };
+            push(state, frame);
+            run(state, frame);
+            Completion::Return(Slot::Object(promise))
+        },
+        _ => {
+            push(state, frame);
+            run(state, frame)
+        }
+    }
+}
+
+/// Resumes the given suspended `frame` of a generator or async function, and
+/// runs it until it completes or is suspended again. Returns the completion
+/// with which the frame was popped. Resuming a frame that is running throws,
+/// and resuming a frame that has completed returns `undefined`.
+pub fn resume(state: &Shared, frame: usize) -> Completion {
+    {
+        let data = &state.borrow().frames[frame];
+        if data.live {
+            return error("TypeError", "already running");
+        }
+        if !data.suspended {
+            return Completion::Return(Slot::Undefined);
+        }
+    }
+    state.borrow_mut().frames[frame].suspended = false;
+    push(state, frame);
+    run(state, frame)
+}
+
+/// Calls the given `callee` as a constructor with the given `arguments`.
+pub fn construct(state: &Shared, callee: usize, arguments: Vec<Slot>) -> Completion {
+    let prototype = match get_property(state, &Slot::Object(callee), &Key::String("prototype".to_owned())) {
+        Ok(Slot::Object(prototype)) => Some(prototype),
+        Ok(_) => None,
+        Err(completion) => return completion
+    };
+    let object = {
+        let mut state = state.borrow_mut();
+        let mut object = ObjectData::new("Object", state.objects[callee].global);
+        object.prototype = prototype;
+        state.add_object(object)
+    };
+    match call(state, callee, Slot::Object(object), arguments, true) {
+        Completion::Return(Slot::Object(result)) => Completion::Return(Slot::Object(result)),
+        Completion::Return(_) => Completion::Return(Slot::Object(object)),
+        completion => completion
+    }
+}
+
+/// Evaluates the given `code` in the given `environment`, extended with the
+/// given `bindings`.
+pub fn evaluate_code(state: &Shared, environment: usize, this: &Slot, code: &str,
+                     bindings: &BTreeMap<String, Slot>) -> Completion {
+    let expression = match parser::parse(code) {
+        Ok(expression) => expression,
+        Err(message) => return error("SyntaxError", &message)
+    };
+    let environment = if bindings.is_empty() {
+        environment
+    } else {
+        let mut state = state.borrow_mut();
+        let global = state.environments[environment].global;
+        state.add_environment(EnvironmentData {
+            kind: EnvironmentType::Declarative,
+            global,
+            parent: Some(environment),
+            bindings: bindings.iter().map(|(name, value)| (name.clone(), value.clone())).collect(),
+            object: None,
+            callee: None,
+            optimized_out: false
+        })
+    };
+    match evaluate(state, environment, this, &expression) {
+        Ok(value) => Completion::Return(value),
+        Err(completion) => completion
+    }
+}
+
+/// Adds a new frame that is not yet on the stack, and returns its index.
+#[allow(clippy::too_many_arguments)]
+fn add_frame(state: &Shared, kind: FrameType, global: usize, script: usize, environment: usize,
+             callee: Option<usize>, this: Slot, arguments: Vec<Slot>,
+             constructing: bool) -> usize {
+    let mut state = state.borrow_mut();
+    state.frames.push(FrameData {
+        kind,
+        global,
+        script,
+        offset: 0,
+        depth: 0,
+        environment,
+        variables: environment,
+        callee,
+        this,
+        arguments,
+        constructing,
+        live: false,
+        suspended: false,
+        promise: None,
+        older: None,
+        pop_handler: None,
+        step_handler: None
+    });
+    state.frames.len() - 1
+}
+
+/// Pushes the given `frame` on the stack.
+fn push(state: &Shared, frame: usize) {
+    let mut state = state.borrow_mut();
+    let older = state.stack.last().cloned();
+    let depth = state.stack.len() as u32;
+    let data = &mut state.frames[frame];
+    data.older = older;
+    data.depth = depth;
+    data.live = true;
+    state.stack.push(frame);
+}
+
+/// Runs the given `frame` until it completes, and then pops it from the stack.
+fn run(state: &Shared, frame: usize) -> Completion {
+    if let Some(completion) = enter(state, frame) {
+        return pop(state, frame, completion);
+    }
+    let completion = loop {
+        let (script, offset) = {
+            let state = state.borrow();
+            (state.frames[frame].script, state.frames[frame].offset)
+        };
+        let instruction = state.borrow().scripts[script].instructions.get(offset as usize).cloned();
+        let instruction = match instruction {
+            Some(instruction) => instruction,
+            None => break Completion::Return(Slot::Undefined)
+        };
+        if let Some(completion) = hit(state, frame, script, offset) {
+            break completion;
+        }
+        if let Operation::Debugger = instruction.operation {
+            if let Some(completion) = debugger_statement(state, frame) {
+                break completion;
+            }
+        }
+        match perform(state, frame, instruction.operation) {
+            Ok(None) => state.borrow_mut().frames[frame].offset += 1,
+            Ok(Some(completion @ Completion::Yield(_))) | Ok(Some(completion @ Completion::Await(_))) => {
+                state.borrow_mut().frames[frame].offset += 1;
+                break completion;
+            },
+            Ok(Some(Completion::Throw(exception))) | Err(Completion::Throw(exception)) => {
+                break unwind(state, frame, exception);
+            },
+            Ok(Some(completion)) | Err(completion) => break completion
+        }
+    };
+    pop(state, frame, completion)
+}
+
+/// Calls the given `handlers` with a wrapper to the given `frame`, by means of
+/// the given `handle` function, until one of them returns a resumption value.
+/// Returns the completion corresponding to that resumption value, if any.
+fn dispatch<T, F>(state: &Shared, frame: usize, handlers: Vec<T>, handle: F) -> Option<Completion>
+    where F: Fn(&T, &Frame<Reference>) -> ResumptionValue<Reference>
+{
+    let wrapper = Frame::from_handle(Handle::new(state, frame));
+    handlers.iter().filter_map(|handler| handle(handler, &wrapper)).next().map(|resumption| {
+        to_completion(&resumption)
+    })
+}
+
+/// Calls the enter frame handlers of the debuggers observing the given
+/// `frame`.
+fn enter(state: &Shared, frame: usize) -> Option<Completion> {
+    let handlers = {
+        let state = state.borrow();
+        state.handlers(state.frames[frame].global, |debugger| &debugger.enter_frame_handler)
+    };
+    dispatch(state, frame, handlers, |handler, frame| handler.handle(frame))
+}
+
+/// Calls the debugger statement handlers of the debuggers observing the given
+/// `frame`.
+fn debugger_statement(state: &Shared, frame: usize) -> Option<Completion> {
+    let handlers = {
+        let state = state.borrow();
+        state.handlers(state.frames[frame].global, |debugger| &debugger.debugger_statement_handler)
+    };
+    dispatch(state, frame, handlers, |handler, frame| handler.handle(frame))
+}
+
+/// Calls the exception unwind handlers of the debuggers observing the given
+/// `frame` with the given `exception`. Returns the completion with which the
+/// frame should be popped.
+fn unwind(state: &Shared, frame: usize, exception: Slot) -> Completion {
+    let handlers = {
+        let state = state.borrow();
+        state.handlers(state.frames[frame].global, |debugger| &debugger.exception_unwind_handler)
+    };
+    let value = to_value(state, &exception);
+    dispatch(state, frame, handlers, |handler, frame| {
+        handler.handle(frame, &value)
+    }).unwrap_or(Completion::Throw(exception))
+}
+
+/// Calls the step handler of the given `frame`, followed by the handlers for
+/// the enabled breakpoints at the given `offset` in the given `script`. If any
+/// of them returns a resumption value, returns the corresponding completion.
+fn hit(state: &Shared, frame: usize, script: usize, offset: u32) -> Option<Completion> {
+    let (step_handler, breakpoint_handlers) = {
+        let state = state.borrow();
+        if !state.is_observed(state.frames[frame].global) {
+            return None;
+        }
+        (state.frames[frame].step_handler.clone(),
+         state.scripts[script].breakpoints.get(&offset).map_or_else(Vec::new, |breakpoints| {
+             breakpoints.iter().filter(|breakpoint| breakpoint.enabled).map(|breakpoint| {
+                 breakpoint.handler.clone()
+             }).collect()
+         }))
+    };
+    dispatch(state, frame, step_handler.into_iter().collect(), |handler, frame| {
+        handler.handle(frame)
+    }).or_else(|| dispatch(state, frame, breakpoint_handlers, |handler, frame| {
+        handler.handle(frame)
+    }))
+}
+
+/// Calls the pop handler of the given `frame` with the given `completion`, and
+/// then pops the frame from the stack. If the frame completed rather than
+/// being suspended, and it is the frame of an async function call, settles
+/// the promise returned by the call. Returns the completion with which the
+/// frame was popped.
+fn pop(state: &Shared, frame: usize, completion: Completion) -> Completion {
+    let handler = {
+        let state = state.borrow();
+        if state.is_observed(state.frames[frame].global) {
+            state.frames[frame].pop_handler.clone()
+        } else {
+            None
+        }
+    };
+    let completion = match handler {
+        Some(handler) => {
+            let wrapper = Frame::from_handle(Handle::new(state, frame));
+            match handler.handle(&wrapper, &to_completion_value(state, &completion)) {
+                Some(resumption) => to_completion(&resumption),
+                None => completion
+            }
+        },
+        None => completion
+    };
+    let mut state = state.borrow_mut();
+    let suspended = matches!(completion, Completion::Yield(_) | Completion::Await(_));
+    state.frames[frame].live = false;
+    state.frames[frame].suspended = suspended;
+    state.stack.pop();
+    if let Some(promise) = state.frames[frame].promise.filter(|_| !suspended) {
+        match completion {
+            Completion::Return(ref value) => state.settle_promise(promise, PromiseState::Fulfilled, value.clone()),
+            Completion::Throw(ref value) => state.settle_promise(promise, PromiseState::Rejected, value.clone()),
+            _ => {}
+        }
+    }
+    completion
+}
+
+/// Performs the given `operation` in the given `frame`. If the operation
+/// completes the frame, returns the corresponding completion.
+fn perform(state: &Shared, frame: usize, operation: Operation) -> Result<Option<Completion>, Completion> {
+    let (environment, variables, this) = {
+        let state = state.borrow();
+        let frame = &state.frames[frame];
+        (frame.environment, frame.variables, frame.this.clone())
+    };
+    match operation {
+        Operation::Debugger => {},
+        Operation::Expression(expression) => {
+            evaluate(state, environment, &this, &expression)?;
+        },
+        Operation::Var(name, expression) => {
+            let value = evaluate(state, environment, &this, &expression)?;
+            declare(state, variables, &name, value);
+        },
+        Operation::Return(expression) => {
+            return Ok(Some(Completion::Return(evaluate(state, environment, &this, &expression)?)));
+        },
+        Operation::Throw(expression) => {
+            return Ok(Some(Completion::Throw(evaluate(state, environment, &this, &expression)?)));
+        },
+        Operation::Yield(expression) => {
+            return Ok(Some(Completion::Yield(evaluate(state, environment, &this, &expression)?)));
+        },
+        Operation::Await(expression) => {
+            return Ok(Some(Completion::Await(evaluate(state, environment, &this, &expression)?)));
+        },
+        Operation::EnterWith(expression) => {
+            let object = match evaluate(state, environment, &this, &expression)? {
+                Slot::Object(object) => object,
+                _ => return Err(error("TypeError", "with requires an object"))
+            };
+            let mut state = state.borrow_mut();
+            let global = state.environments[environment].global;
+            let with = state.add_environment(EnvironmentData {
+                kind: EnvironmentType::With,
+                global,
+                parent: Some(environment),
+                bindings: Vec::new(),
+                object: Some(object),
+                callee: None,
+                optimized_out: false
+            });
+            state.frames[frame].environment = with;
+        },
+        Operation::LeaveWith => {
+            let mut state = state.borrow_mut();
+            // Scripts are checked for a matching `EnterWith` when created, so
+            // the environment is always the one that it introduced.
+            let parent = state.environments[environment].parent.expect("unmatched leave with");
+            state.frames[frame].environment = parent;
+        },
+        Operation::Function(script) => {
+            let function = create_function(state, script, environment);
+            let name = state.borrow().scripts[script].name.clone().unwrap_or_default();
+            declare(state, variables, &name, Slot::Object(function));
+        }
+    }
+    Ok(None)
+}
+
+/// Creates a function for the given `script` that closes over the given
+/// `environment`.
+fn create_function(state: &Shared, script: usize, environment: usize) -> usize {
+    let mut state = state.borrow_mut();
+    let (global, name, parameters, kind) = {
+        let script = &state.scripts[script];
+        (script.global, script.name.clone(), script.parameters.clone(), script.kind)
+    };
+    let prototype = state.add_object(ObjectData::new("Object", global));
+    let mut function = ObjectData::new("Function", global);
+    function.function = Some(FunctionData {
+        name: name.clone(),
+        display_name: name,
+        parameters,
+        script: Some(script),
+        environment: Some(environment),
+        kind,
+        bound: None
+    });
+    function.properties.push((Key::String("prototype".to_owned()), Property {
+        configurable: false,
+        enumerable: false,
+        kind: PropertyKind::Data { value: Slot::Object(prototype), writable: true }
+    }));
+    state.add_object(function)
+}
+
+/// Binds the given `name` to the given `value` in the given variable
+/// `environment`.
+fn declare(state: &Shared, environment: usize, name: &str, value: Slot) {
+    let mut state = state.borrow_mut();
+    match state.environments[environment].object {
+        Some(object) => {
+            let property = Property {
+                configurable: false,
+                enumerable: true,
+                kind: PropertyKind::Data { value, writable: true }
+            };
+            let key = Key::from_name(name);
+            let object = &mut state.objects[object];
+            match object.property_mut(&key) {
+                Some(existing) => *existing = property,
+                None => object.properties.push((key, property))
+            }
+        },
+        None => {
+            let bindings = &mut state.environments[environment].bindings;
+            match bindings.iter_mut().find(|binding| binding.0 == name) {
+                Some(binding) => binding.1 = value,
+                None => bindings.push((name.to_owned(), value))
+            }
+        }
+    }
+}
+
+/// Returns the innermost environment, starting from the given `environment`,
+/// that binds the given `name`.
+pub fn resolve(state: &Shared, environment: usize, name: &str) -> Option<usize> {
+    let mut current = Some(environment);
+    while let Some(environment) = current {
+        let (object, parent, bound) = {
+            let state = state.borrow();
+            let data = &state.environments[environment];
+            (data.object, data.parent, data.bindings.iter().any(|binding| binding.0 == name))
+        };
+        let bound = match object {
+            Some(object) => find_property(state, object, &Key::from_name(name)).is_some(),
+            None => bound
+        };
+        if bound {
+            return Some(environment);
+        }
+        current = parent;
+    }
+    None
+}
+
+/// Returns the object on the prototype chain of the given `object` that has an
+/// own property with the given `key`, together with that property.
+pub fn find_property(state: &Shared, object: usize, key: &Key) -> Option<(usize, Property)> {
+    let state = state.borrow();
+    let mut current = Some(object);
+    while let Some(object) = current {
+        if let Some(property) = state.objects[object].property(key) {
+            return Some((object, property.clone()));
+        }
+        current = state.objects[object].prototype;
+    }
+    None
+}
+
+/// If the given `value` is a proxy, returns its target. Throws a `TypeError`
+/// if the proxy has been revoked.
+fn proxy_target(state: &Shared, value: &Slot) -> Result<Option<usize>, Completion> {
+    let state = state.borrow();
+    let proxy = match *value {
+        Slot::Object(object) => state.objects[object].proxy.as_ref(),
+        _ => None
+    };
+    match proxy {
+        Some(proxy) if proxy.revoked => Err(error("TypeError", "proxy has been revoked")),
+        Some(proxy) => Ok(Some(proxy.target)),
+        None => Ok(None)
+    }
+}
+
+fn lookup(state: &Shared, environment: usize, name: &str) -> Evaluation {
+    let environment = match resolve(state, environment, name) {
+        Some(environment) => environment,
+        None => return Err(error("ReferenceError", &format!("{} is not defined", name)))
+    };
+    let object = state.borrow().environments[environment].object;
+    match object {
+        Some(object) => get_property(state, &Slot::Object(object), &Key::from_name(name)),
+        None => {
+            let state = state.borrow();
+            let bindings = &state.environments[environment].bindings;
+            Ok(bindings.iter().find(|binding| binding.0 == name).unwrap().1.clone())
+        }
+    }
+}
+
+fn assign(state: &Shared, environment: usize, name: &str, value: Slot) -> Result<(), Completion> {
+    let target = match resolve(state, environment, name) {
+        Some(environment) => environment,
+        None => {
+            let state = state.borrow();
+            let global = state.environments[environment].global;
+            state.objects[global].environment.unwrap()
+        }
+    };
+    let object = state.borrow().environments[target].object;
+    match object {
+        Some(object) => set_property(state, &Slot::Object(object), &Key::from_name(name), value),
+        None => {
+            let mut state = state.borrow_mut();
+            let bindings = &mut state.environments[target].bindings;
+            bindings.iter_mut().find(|binding| binding.0 == name).unwrap().1 = value;
+            Ok(())
+        }
+    }
+}
+
+/// Returns the value of the property with the given `key` on the given
+/// `value`, calling its getter if it is an accessor property.
+pub fn get_property(state: &Shared, value: &Slot, key: &Key) -> Evaluation {
+    if let Some(target) = proxy_target(state, value)? {
+        return get_property(state, &Slot::Object(target), key);
+    }
+    let object = match *value {
+        Slot::Object(object) => object,
+        Slot::String(ref string) if matches!(*key, Key::String(ref name) if name == "length") => {
+            return Ok(Slot::Number(string.chars().count() as f64));
+        },
+        Slot::Undefined | Slot::Null => {
+            return Err(error("TypeError", &format!("cannot read property {} of {}", key,
+                                                  to_string(state, value))));
+        },
+        _ => return Ok(Slot::Undefined)
+    };
+    match find_property(state, object, key) {
+        Some((_, Property { kind: PropertyKind::Data { value, .. }, .. })) => Ok(value),
+        Some((_, Property { kind: PropertyKind::Accessor { get: Slot::Object(getter), .. }, .. })) => {
+            evaluation(call(state, getter, Slot::Object(object), Vec::new(), false))
+        },
+        _ => Ok(Slot::Undefined)
+    }
+}
+
+/// Sets the value of the property with the given `name` on the given `value`,
+/// calling its setter if it is an accessor property.
+pub fn set_property(state: &Shared, value: &Slot, key: &Key, new_value: Slot) -> Result<(), Completion> {
+    if let Some(target) = proxy_target(state, value)? {
+        return set_property(state, &Slot::Object(target), key, new_value);
+    }
+    let object = match *value {
+        Slot::Object(object) => object,
+        Slot::Undefined | Slot::Null => {
+            return Err(error("TypeError", &format!("cannot set property {} of {}", key,
+                                                  to_string(state, value))));
+        },
+        _ => return Ok(())
+    };
+    match find_property(state, object, key) {
+        Some((_, Property { kind: PropertyKind::Accessor { set, .. }, .. })) => {
+            if let Slot::Object(setter) = set {
+                evaluation(call(state, setter, Slot::Object(object), vec![new_value], false))?;
+            }
+        },
+        Some((owner, Property { kind: PropertyKind::Data { writable: true, .. }, .. })) if owner == object => {
+            let mut state = state.borrow_mut();
+            if let Some(&mut Property { kind: PropertyKind::Data { ref mut value, .. }, .. }) = state.objects[object].property_mut(key) {
+                *value = new_value;
+            }
+        },
+        Some((_, Property { kind: PropertyKind::Data { writable: false, .. }, .. })) => {},
+        _ => {
+            let mut state = state.borrow_mut();
+            if state.objects[object].extensible {
+                state.objects[object].properties.push((key.clone(), Property {
+                    configurable: true,
+                    enumerable: true,
+                    kind: PropertyKind::Data { value: new_value, writable: true }
+                }));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Evaluates the given `expression` in the given `environment`.
+pub fn evaluate(state: &Shared, environment: usize, this: &Slot, expression: &Expression) -> Evaluation {
+    match *expression {
+        Expression::Literal(ref value) => Ok(value.clone()),
+        Expression::Identifier(ref name) => lookup(state, environment, name),
+        Expression::This => Ok(this.clone()),
+        Expression::Member(ref object, ref key) => {
+            let object = evaluate(state, environment, this, object)?;
+            get_property(state, &object, key)
+        },
+        Expression::Call(ref callee, ref arguments) => {
+            let (function, receiver) = match **callee {
+                Expression::Member(ref object, ref key) => {
+                    let object = evaluate(state, environment, this, object)?;
+                    (get_property(state, &object, key)?, object)
+                },
+                _ => (evaluate(state, environment, this, callee)?, Slot::Undefined)
+            };
+            let arguments = arguments.iter().map(|argument| {
+                evaluate(state, environment, this, argument)
+            }).collect::<Result<Vec<_>, _>>()?;
+            match function {
+                Slot::Object(function) if state.borrow().objects[function].function.is_some() => {
+                    evaluation(call(state, function, receiver, arguments, false))
+                },
+                _ => Err(error("TypeError", "not a function"))
+            }
+        },
+        Expression::Assign(ref target, ref value) => {
+            let value = evaluate(state, environment, this, value)?;
+            match **target {
+                Expression::Identifier(ref name) => assign(state, environment, name, value.clone())?,
+                Expression::Member(ref object, ref key) => {
+                    let object = evaluate(state, environment, this, object)?;
+                    set_property(state, &object, key, value.clone())?;
+                },
+                _ => unreachable!()
+            }
+            Ok(value)
+        },
+        Expression::Unary(operator, ref operand) => {
+            let operand = evaluate(state, environment, this, operand)?;
+            Ok(match operator {
+                UnaryOperator::Not => Slot::Boolean(!is_truthy(state, &operand)),
+                UnaryOperator::Negate => match operand {
+                    Slot::BigInt(value) => Slot::BigInt(BigInt::new(!value.is_negative(), value.digits().to_vec())),
+                    Slot::Symbol(_) => return Err(error("TypeError", "cannot convert a symbol to a number")),
+                    operand => Slot::Number(-to_number(&operand))
+                }
+            })
+        },
+        Expression::Binary(BinaryOperator::And, ref left, ref right) => {
+            let left = evaluate(state, environment, this, left)?;
+            if is_truthy(state, &left) { evaluate(state, environment, this, right) } else { Ok(left) }
+        },
+        Expression::Binary(BinaryOperator::Or, ref left, ref right) => {
+            let left = evaluate(state, environment, this, left)?;
+            if is_truthy(state, &left) { Ok(left) } else { evaluate(state, environment, this, right) }
+        },
+        Expression::Binary(operator, ref left, ref right) => {
+            let left = evaluate(state, environment, this, left)?;
+            let right = evaluate(state, environment, this, right)?;
+            binary(state, operator, &left, &right)
+        }
+    }
+}
+
+fn binary(state: &Shared, operator: BinaryOperator, left: &Slot, right: &Slot) -> Evaluation {
+    Ok(match (operator, left, right) {
+        (BinaryOperator::Equal, _, _) => Slot::Boolean(left == right),
+        (BinaryOperator::NotEqual, _, _) => Slot::Boolean(left != right),
+        (_, &Slot::Symbol(_), _) | (_, _, &Slot::Symbol(_)) => {
+            return Err(error("TypeError", "cannot convert a symbol to a primitive"));
+        },
+        (BinaryOperator::Add, &Slot::String(_), _) | (BinaryOperator::Add, _, &Slot::String(_)) => {
+            Slot::String(to_string(state, left) + &to_string(state, right))
+        },
+        (BinaryOperator::Add, &Slot::BigInt(_), _) | (BinaryOperator::Add, _, &Slot::BigInt(_)) |
+        (BinaryOperator::Subtract, &Slot::BigInt(_), _) | (BinaryOperator::Subtract, _, &Slot::BigInt(_)) => {
+            return Err(error("TypeError", "BigInt arithmetic is not supported"));
+        },
+        (BinaryOperator::Add, _, _) => Slot::Number(to_number(left) + to_number(right)),
+        (BinaryOperator::Subtract, _, _) => Slot::Number(to_number(left) - to_number(right)),
+        (_, Slot::String(left), Slot::String(right)) => Slot::Boolean(match operator {
+            BinaryOperator::Less => left < right,
+            BinaryOperator::LessOrEqual => left <= right,
+            BinaryOperator::Greater => left > right,
+            _ => left >= right
+        }),
+        _ => {
+            let (left, right) = (to_number(left), to_number(right));
+            Slot::Boolean(match operator {
+                BinaryOperator::Less => left < right,
+                BinaryOperator::LessOrEqual => left <= right,
+                BinaryOperator::Greater => left > right,
+                _ => left >= right
+            })
+        }
+    })
+}
+
+pub fn is_truthy(state: &Shared, value: &Slot) -> bool {
+    to_value(state, value).is_truthy()
+}
+
+pub fn to_number(value: &Slot) -> f64 {
+    match *value {
+        Slot::Undefined | Slot::Symbol(_) | Slot::Object(_) => f64::NAN,
+        Slot::Null => 0.0,
+        Slot::Boolean(value) => if value { 1.0 } else { 0.0 },
+        Slot::String(ref value) => {
+            let value = value.trim();
+            if value.is_empty() { 0.0 } else { value.parse().unwrap_or(f64::NAN) }
+        },
+        Slot::Number(value) => value,
+        Slot::BigInt(ref value) => {
+            let magnitude = value.digits().iter().rev().fold(0.0, |magnitude, &digit| {
+                magnitude * 4294967296.0 + f64::from(digit)
+            });
+            if value.is_negative() { -magnitude } else { magnitude }
+        }
+    }
+}
+
+pub fn to_string(state: &Shared, value: &Slot) -> String {
+    to_value(state, value).to_string()
+}
@@ -0,0 +1,1481 @@
+//! A reference backend that simulates a debuggee in memory.
+//!
+//! The simulated debuggee consists of globals, objects, environments, scripts
+//! and sources, all of which live in a `Runtime`. Scripts are built with a
+//! `ScriptBuilder` as a sequence of operations, each of which is located at a
+//! line, and occupies a single offset. Operations refer to values by means of
+//! a small subset of JavaScript expressions, consisting of literals,
+//! identifiers, `this`, member accesses, calls, assignments, and the most
+//! common unary and binary operators. The same subset is understood by
+//! `Frame::eval` and `Object::execute_in_global`. Literals include BigInt
+//! literals such as `1n`, but arithmetic on BigInts and symbols is not
+//! supported, and throws instead. Exceptions thrown by the runtime itself are
+//! strings of the form `"TypeError: message"`. The kind of a function only
+//! affects how it can be called: class constructors throw unless called as a
+//! constructor, and only ordinary functions and class constructors can be
+//! called as one.
+//!
+//! When a script is executed, the runtime pushes a frame for it on the stack,
+//! and executes its operations one offset at a time, pushing a new frame for
+//! each function call. Before each operation, the step handler of the frame is
+//! called, followed by the handlers for the enabled breakpoints at the offset
+//! of the operation, in the order in which they were set. When a frame is
+//! popped from the stack, its pop handler is called. The handlers of a debugger
+//! are called when a top-level script is created, when a frame is pushed on the
+//! stack, when a `debugger` statement is executed, and when an exception is
+//! thrown or propagates into a frame from a frame it called. Handlers are only
+//! called for code running in a global that is a debuggee of an enabled
+//! debugger.
+//!
+//! Calling a generator creates its frame without running it, and returns a
+//! generator object. Calling an async function runs its frame until it
+//! completes or awaits, and returns a promise that settles when the frame
+//! completes. Either way, a suspended frame only runs again when it is resumed
+//! by `Runtime::resume`, which pushes it on the stack again.
+//!
+//! The size of an object, as counted by a census, is a fixed number of bytes,
+//! plus a fixed number of bytes for each of its properties, including private
+//! ones, and, if it is a collection, each of its entries, plus a fixed number
+//! of bytes if it is a function. The size of an array buffer also includes the
+//! bytes it holds. The timestamp of an allocation is the number of allocations
+//! recorded up to and including it.
+//!
+//! Unlike a real engine, the runtime does not scope wrappers to the debugger
+//! from which they were obtained: an environment or frame is considered to be
+//! a debuggee environment or frame if its global is a debuggee of any
+//! debugger.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use {CompletionValue, Debugger, ElementType, Environment, FunctionKind, IntroductionType, Object,
+     PromiseState, PropertyKey, Script, Source, Symbol, Value};
+
+mod backend;
+mod interpreter;
+mod parser;
+mod state;
+
+use self::interpreter::Completion;
+use self::state::{BoundFunction, BufferData, CollectionData, DebuggerData, EnvironmentData,
+                  ErrorData, FunctionData, Instruction, Key, ObjectData, Operation,
+                  Property, PropertyKind, ProxyData, ScriptData, Slot, SourceData, State,
+                  SymbolData, ViewData};
+
+type Shared = Rc<RefCell<State>>;
+
+/// The reference backend. Wrappers for this backend are obtained from a
+/// `Runtime`.
+pub enum Reference {}
+
+/// A handle to an entity in a `Runtime`. Handles are used for every wrapper
+/// type of the reference backend.
+pub struct Handle {
+    state: Shared,
+    index: usize
+}
+
+impl Handle {
+    fn new(state: &Shared, index: usize) -> Handle {
+        Handle {
+            state: state.clone(),
+            index
+        }
+    }
+}
+
+impl Clone for Handle {
+    fn clone(&self) -> Handle {
+        Handle::new(&self.state, self.index)
+    }
+}
+
+impl PartialEq for Handle {
+    fn eq(&self, other: &Handle) -> bool {
+        Rc::ptr_eq(&self.state, &other.state) && self.index == other.index
+    }
+}
+
+impl Eq for Handle {}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("Handle").field(&self.index).finish()
+    }
+}
+
+fn to_value(state: &Shared, slot: &Slot) -> Value<Reference> {
+    match *slot {
+        Slot::Undefined => Value::Undefined,
+        Slot::Null => Value::Null,
+        Slot::Boolean(value) => Value::Boolean(value),
+        Slot::String(ref value) => Value::String(value.clone()),
+        Slot::Number(value) => Value::Number(value),
+        Slot::BigInt(ref value) => Value::BigInt(value.clone()),
+        Slot::Symbol(index) => Value::Symbol(Symbol::from_handle(Handle::new(state, index))),
+        Slot::Object(index) => Value::Object(Object::from_handle(Handle::new(state, index)))
+    }
+}
+
+fn to_slot(value: &Value<Reference>) -> Slot {
+    match *value {
+        Value::Undefined => Slot::Undefined,
+        Value::Null => Slot::Null,
+        Value::Boolean(value) => Slot::Boolean(value),
+        Value::String(ref value) => Slot::String(value.clone()),
+        Value::Number(value) => Slot::Number(value),
+        Value::BigInt(ref value) => Slot::BigInt(value.clone()),
+        Value::Symbol(ref symbol) => Slot::Symbol(symbol.handle().index),
+        Value::Object(ref object) => Slot::Object(object.handle().index)
+    }
+}
+
+fn to_key(key: &PropertyKey<Reference>) -> Key {
+    match *key {
+        PropertyKey::String(ref name) => Key::from_name(name),
+        PropertyKey::Symbol(ref symbol) => Key::Symbol(symbol.handle().index),
+        PropertyKey::Index(index) => Key::Index(index)
+    }
+}
+
+fn to_property_key(state: &Shared, key: &Key) -> PropertyKey<Reference> {
+    match *key {
+        Key::String(ref name) => PropertyKey::String(name.clone()),
+        Key::Symbol(index) => PropertyKey::Symbol(Symbol::from_handle(Handle::new(state, index))),
+        Key::Index(index) => PropertyKey::Index(index)
+    }
+}
+
+fn to_completion_value(state: &Shared, completion: &Completion) -> CompletionValue<Reference> {
+    match *completion {
+        Completion::Return(ref value) => CompletionValue::Return(to_value(state, value)),
+        Completion::Throw(ref value) => CompletionValue::Throw(to_value(state, value)),
+        Completion::Terminate => CompletionValue::Terminate,
+        Completion::Yield(ref value) => CompletionValue::Yield(to_value(state, value)),
+        Completion::Await(ref value) => CompletionValue::Await(to_value(state, value))
+    }
+}
+
+fn to_completion(completion: &CompletionValue<Reference>) -> Completion {
+    match *completion {
+        CompletionValue::Return(ref value) => Completion::Return(to_slot(value)),
+        CompletionValue::Throw(ref value) => Completion::Throw(to_slot(value)),
+        CompletionValue::Terminate => Completion::Terminate,
+        CompletionValue::Yield(ref value) | CompletionValue::Await(ref value) => Completion::Return(to_slot(value))
+    }
+}
+
+/// An operation in a script built with a `ScriptBuilder`. Expressions are
+/// given as source code.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    /// A `debugger` statement.
+    Debugger,
+
+    /// An expression statement.
+    Expression(String),
+
+    /// A `var` declaration that binds the given name to the value of the given
+    /// expression.
+    Var(String, String),
+
+    /// A `return` statement that returns the value of the given expression.
+    Return(String),
+
+    /// A `throw` statement that throws the value of the given expression.
+    Throw(String),
+
+    /// A `yield` statement that yields the value of the given expression. Only
+    /// valid in the body of a generator.
+    Yield(String),
+
+    /// An `await` statement that awaits the value of the given expression.
+    /// Only valid in the body of an async function.
+    Await(String),
+
+    /// The start of a `with` statement for the object that is the value of the
+    /// given expression.
+    EnterWith(String),
+
+    /// The end of the innermost `with` statement. Only valid inside a `with`
+    /// statement.
+    LeaveWith
+}
+
+enum Step {
+    Op(Op),
+    Function(usize)
+}
+
+/// A builder for scripts in a `Runtime`.
+pub struct ScriptBuilder {
+    start_line: u32,
+    source_start: u32,
+    source_length: u32,
+    display_name: Option<String>,
+    name: Option<String>,
+    parameters: Vec<String>,
+    kind: FunctionKind,
+    steps: Vec<(u32, u32, Step)>,
+    children: Vec<ScriptBuilder>
+}
+
+impl ScriptBuilder {
+    /// Returns a builder for a script that starts at the given `start_line`.
+    pub fn new(start_line: u32) -> ScriptBuilder {
+        ScriptBuilder {
+            start_line,
+            source_start: 0,
+            source_length: 0,
+            display_name: None,
+            name: None,
+            parameters: Vec::new(),
+            kind: FunctionKind::Normal,
+            steps: Vec::new(),
+            children: Vec::new()
+        }
+    }
+
+    /// Sets the display name of the script.
+    pub fn display_name(mut self, display_name: &str) -> ScriptBuilder {
+        self.display_name = Some(display_name.to_owned());
+        self
+    }
+
+    /// Sets the range of characters spanned by the code of the script in its
+    /// source to the `length` characters starting at `start`.
+    pub fn source_range(mut self, start: u32, length: u32) -> ScriptBuilder {
+        self.source_start = start;
+        self.source_length = length;
+        self
+    }
+
+    /// Sets the kind of the function for which the script is the body. The
+    /// default is `FunctionKind::Normal`. The kind has no effect on scripts
+    /// that are not the body of a function.
+    pub fn kind(mut self, kind: FunctionKind) -> ScriptBuilder {
+        self.kind = kind;
+        self
+    }
+
+    /// Appends the given `op`, located at column 0 of the given `line`, to the
+    /// script.
+    pub fn op(self, line: u32, op: Op) -> ScriptBuilder {
+        self.op_at(line, 0, op)
+    }
+
+    /// Appends the given `op`, located at the given `line` and `column`, to
+    /// the script.
+    pub fn op_at(mut self, line: u32, column: u32, op: Op) -> ScriptBuilder {
+        self.steps.push((line, column, Step::Op(op)));
+        self
+    }
+
+    /// Appends a declaration for a function with the given `name` and
+    /// `parameters`, located at column 0 of the given `line`, to the script.
+    /// The given `body` becomes a child script of the script.
+    pub fn function(mut self, line: u32, name: &str, parameters: &[&str],
+                    mut body: ScriptBuilder) -> ScriptBuilder {
+        body.name = Some(name.to_owned());
+        body.parameters = parameters.iter().map(|&parameter| parameter.to_owned()).collect();
+        self.steps.push((line, 0, Step::Function(self.children.len())));
+        self.children.push(body);
+        self
+    }
+}
+
+/// A builder for sources in a `Runtime`.
+pub struct SourceBuilder {
+    url: String,
+    text: String,
+    source_map_url: Option<String>,
+    element: Option<(Object<Reference>, Option<String>)>,
+    introduction_type: Option<IntroductionType>,
+    introduction_site: Option<(Script<Reference>, u32)>
+}
+
+impl SourceBuilder {
+    /// Returns a builder for a source with the given `text`, loaded from the
+    /// document at the given `url`.
+    pub fn new(url: &str, text: &str) -> SourceBuilder {
+        SourceBuilder {
+            url: url.to_owned(),
+            text: text.to_owned(),
+            source_map_url: None,
+            element: None,
+            introduction_type: None,
+            introduction_site: None
+        }
+    }
+
+    /// Sets the URL of the source map of the source.
+    pub fn source_map_url(mut self, url: &str) -> SourceBuilder {
+        self.source_map_url = Some(url.to_owned());
+        self
+    }
+
+    /// Sets the DOM element that introduced the source, and the name of the
+    /// attribute on that element, if any.
+    pub fn element(mut self, element: &Object<Reference>, attribute_name: Option<&str>) -> SourceBuilder {
+        self.element = Some((element.clone(), attribute_name.map(|name| name.to_owned())));
+        self
+    }
+
+    /// Sets the introduction type of the source.
+    pub fn introduction_type(mut self, introduction_type: IntroductionType) -> SourceBuilder {
+        self.introduction_type = Some(introduction_type);
+        self
+    }
+
+    /// Sets the script and offset of the call that introduced the source.
+    pub fn introduction_site(mut self, script: &Script<Reference>, offset: u32) -> SourceBuilder {
+        self.introduction_site = Some((script.clone(), offset));
+        self
+    }
+}
+
+/// A simulated debuggee.
+pub struct Runtime {
+    state: Shared
+}
+
+impl Default for Runtime {
+    fn default() -> Runtime {
+        Runtime::new()
+    }
+}
+
+impl Runtime {
+    /// Returns a new runtime, without any globals or debuggers.
+    pub fn new() -> Runtime {
+        Runtime {
+            state: Rc::new(RefCell::new(State::default()))
+        }
+    }
+
+    /// Returns a new debugger for the runtime, without any debuggees.
+    pub fn new_debugger(&self) -> Debugger<Reference> {
+        let mut state = self.state.borrow_mut();
+        state.debuggers.push(DebuggerData {
+            debuggees: Vec::new(),
+            enabled: true,
+            recording_allocations: false,
+            allocations_log: Vec::new(),
+            debugger_statement_handler: None,
+            enter_frame_handler: None,
+            exception_unwind_handler: None,
+            new_script_handler: None
+        });
+        Debugger::from_handle(Handle::new(&self.state, state.debuggers.len() - 1))
+    }
+
+    /// Returns a new global, with an empty global environment.
+    pub fn new_global(&self) -> Object<Reference> {
+        let mut state = self.state.borrow_mut();
+        let global = state.objects.len();
+        let environment = state.add_environment(EnvironmentData {
+            kind: ::EnvironmentType::Object,
+            global,
+            parent: None,
+            bindings: Vec::new(),
+            object: Some(global),
+            callee: None,
+            optimized_out: false
+        });
+        let mut object = ObjectData::new("global", global);
+        object.environment = Some(environment);
+        state.add_object(object);
+        Object::from_handle(Handle::new(&self.state, global))
+    }
+
+    /// Returns a new object in the given `global`, with the given `class`.
+    pub fn new_object(&self, global: &Object<Reference>, class: &str) -> Object<Reference> {
+        let mut state = self.state.borrow_mut();
+        let object = state.add_object(ObjectData::new(class, global.handle().index));
+        Object::from_handle(Handle::new(&self.state, object))
+    }
+
+    /// Returns a new native function in the given `global`, with the given
+    /// `name`. Calling the function does nothing, and returns `undefined`.
+    pub fn new_native_function(&self, global: &Object<Reference>, name: &str) -> Object<Reference> {
+        let mut state = self.state.borrow_mut();
+        let mut object = ObjectData::new("Function", global.handle().index);
+        object.function = Some(FunctionData {
+            name: Some(name.to_owned()),
+            display_name: None,
+            parameters: Vec::new(),
+            script: None,
+            environment: None,
+            kind: FunctionKind::Normal,
+            bound: None
+        });
+        let object = state.add_object(object);
+        Object::from_handle(Handle::new(&self.state, object))
+    }
+
+    /// Adds a private field with the given `name` and `value` to the given
+    /// `object`, as if by a field declaration in the class that constructed
+    /// it.
+    ///
+    /// # Panics
+    /// Panics if the given `name` does not start with `#`.
+    pub fn add_private_field(&self, object: &Object<Reference>, name: &str, value: &Value<Reference>) {
+        assert!(name.starts_with('#'), "not a private name");
+        let mut state = self.state.borrow_mut();
+        state.objects[object.handle().index].private_fields.push((name.to_owned(), to_slot(value)));
+    }
+
+    /// Adds a private method with the given `name` to the given `object`, as
+    /// if by a method declaration in the class that constructed it.
+    ///
+    /// # Panics
+    /// Panics if the given `name` does not start with `#`.
+    pub fn add_private_method(&self, object: &Object<Reference>, name: &str, method: &Object<Reference>) {
+        let kind = PropertyKind::Data { value: Slot::Object(method.handle().index), writable: false };
+        self.add_private_method_or_accessor(object, name, kind);
+    }
+
+    /// Adds a private accessor with the given `name`, `get` function and `set`
+    /// function to the given `object`, as if by a getter and setter
+    /// declaration in the class that constructed it.
+    ///
+    /// # Panics
+    /// Panics if the given `name` does not start with `#`.
+    pub fn add_private_accessor(&self, object: &Object<Reference>, name: &str, get: &Value<Reference>,
+                                set: &Value<Reference>) {
+        let kind = PropertyKind::Accessor { get: to_slot(get), set: to_slot(set) };
+        self.add_private_method_or_accessor(object, name, kind);
+    }
+
+    fn add_private_method_or_accessor(&self, object: &Object<Reference>, name: &str, kind: PropertyKind) {
+        assert!(name.starts_with('#'), "not a private name");
+        let mut state = self.state.borrow_mut();
+        state.objects[object.handle().index].private_methods.push((name.to_owned(), Property {
+            configurable: false,
+            enumerable: false,
+            kind
+        }));
+    }
+
+    /// Returns a new bound function for the given `target` function, with the
+    /// given `this` value and `arguments`.
+    pub fn bind_function(&self, target: &Object<Reference>, this: &Value<Reference>,
+                         arguments: &[Value<Reference>]) -> Object<Reference> {
+        let mut state = self.state.borrow_mut();
+        let target = target.handle().index;
+        let name = state.objects[target].function.as_ref().and_then(|function| function.name.clone());
+        let mut object = ObjectData::new("Function", state.objects[target].global);
+        object.function = Some(FunctionData {
+            name: name.map(|name| format!("bound {}", name)),
+            display_name: None,
+            parameters: Vec::new(),
+            script: None,
+            environment: None,
+            kind: FunctionKind::Normal,
+            bound: Some(BoundFunction {
+                target,
+                this: to_slot(this),
+                arguments: arguments.iter().map(to_slot).collect()
+            })
+        });
+        let object = state.add_object(object);
+        Object::from_handle(Handle::new(&self.state, object))
+    }
+
+    /// Returns a new error in the given `global`, with the given `name` and
+    /// `message`, as if by calling the constructor for that type of error.
+    /// The location and stack of the error are those of the code that is
+    /// executing, if any.
+    pub fn new_error(&self, global: &Object<Reference>, name: &str, message: &str) -> Object<Reference> {
+        let mut state = self.state.borrow_mut();
+        let mut object = ObjectData::new("Error", global.handle().index);
+        object.properties.push((Key::String("message".to_owned()), Property {
+            configurable: true,
+            enumerable: false,
+            kind: PropertyKind::Data { value: Slot::String(message.to_owned()), writable: true }
+        }));
+        object.error = Some(ErrorData {
+            name: name.to_owned(),
+            message: message.to_owned(),
+            stack: state.save_stack()
+        });
+        let object = state.add_object(object);
+        Object::from_handle(Handle::new(&self.state, object))
+    }
+
+    /// Returns a new `Map` in the given `global`, containing the given
+    /// `entries` in order. Duplicate keys are not removed.
+    pub fn new_map(&self, global: &Object<Reference>, entries: &[(Value<Reference>, Value<Reference>)]) -> Object<Reference> {
+        let entries = entries.iter().map(|(key, value)| (to_slot(key), to_slot(value))).collect();
+        self.new_collection(global, "Map", CollectionData::Map(entries))
+    }
+
+    /// Returns a new `Set` in the given `global`, containing the given
+    /// `values` in order. Duplicate values are not removed.
+    pub fn new_set(&self, global: &Object<Reference>, values: &[Value<Reference>]) -> Object<Reference> {
+        self.new_collection(global, "Set", CollectionData::Set(values.iter().map(to_slot).collect()))
+    }
+
+    /// Returns a new `WeakMap` in the given `global`, containing the given
+    /// `entries` in order. Duplicate keys are not removed.
+    pub fn new_weak_map(&self, global: &Object<Reference>, entries: &[(Object<Reference>, Value<Reference>)]) -> Object<Reference> {
+        let entries = entries.iter().map(|(key, value)| (key.handle().index, to_slot(value))).collect();
+        self.new_collection(global, "WeakMap", CollectionData::WeakMap(entries))
+    }
+
+    /// Returns a new `WeakSet` in the given `global`, containing the given
+    /// `keys` in order. Duplicate keys are not removed.
+    pub fn new_weak_set(&self, global: &Object<Reference>, keys: &[Object<Reference>]) -> Object<Reference> {
+        let keys = keys.iter().map(|key| key.handle().index).collect();
+        self.new_collection(global, "WeakSet", CollectionData::WeakSet(keys))
+    }
+
+    fn new_collection(&self, global: &Object<Reference>, class: &str, collection: CollectionData) -> Object<Reference> {
+        let mut state = self.state.borrow_mut();
+        let mut object = ObjectData::new(class, global.handle().index);
+        object.collection = Some(collection);
+        let object = state.add_object(object);
+        Object::from_handle(Handle::new(&self.state, object))
+    }
+
+    /// Returns a new `ArrayBuffer` in the given `global`, holding a copy of
+    /// the given `bytes`.
+    pub fn new_array_buffer(&self, global: &Object<Reference>, bytes: &[u8]) -> Object<Reference> {
+        self.new_buffer(global, "ArrayBuffer", bytes)
+    }
+
+    /// Returns a new `SharedArrayBuffer` in the given `global`, holding a copy
+    /// of the given `bytes`.
+    pub fn new_shared_array_buffer(&self, global: &Object<Reference>, bytes: &[u8]) -> Object<Reference> {
+        self.new_buffer(global, "SharedArrayBuffer", bytes)
+    }
+
+    fn new_buffer(&self, global: &Object<Reference>, class: &str, bytes: &[u8]) -> Object<Reference> {
+        let mut state = self.state.borrow_mut();
+        let mut object = ObjectData::new(class, global.handle().index);
+        object.buffer = Some(BufferData {
+            shared: class == "SharedArrayBuffer",
+            bytes: Some(bytes.to_vec())
+        });
+        let object = state.add_object(object);
+        Object::from_handle(Handle::new(&self.state, object))
+    }
+
+    /// Returns a new typed array with elements of the given `element_type` on
+    /// the given `buffer`, in the global of the buffer. The typed array starts
+    /// at the given byte `offset` in the buffer, and has the given number of
+    /// elements.
+    ///
+    /// # Panics
+    /// Panics if the given `buffer` is not an array buffer, or if the typed
+    /// array would not fit within it.
+    pub fn new_typed_array(&self, buffer: &Object<Reference>, element_type: ElementType, offset: usize,
+                           length: usize) -> Object<Reference> {
+        let class = format!("{:?}Array", element_type);
+        self.new_view(buffer, &class, offset, length * element_type.size(), Some(element_type))
+    }
+
+    /// Returns a new `DataView` on the given `buffer`, in the global of the
+    /// buffer. The view starts at the given byte `offset` in the buffer, and
+    /// has the given byte `length`.
+    ///
+    /// # Panics
+    /// Panics if the given `buffer` is not an array buffer, or if the view
+    /// would not fit within it.
+    pub fn new_data_view(&self, buffer: &Object<Reference>, offset: usize, length: usize) -> Object<Reference> {
+        self.new_view(buffer, "DataView", offset, length, None)
+    }
+
+    fn new_view(&self, buffer: &Object<Reference>, class: &str, offset: usize, length: usize,
+                element_type: Option<ElementType>) -> Object<Reference> {
+        let mut state = self.state.borrow_mut();
+        let index = buffer.handle().index;
+        let global = {
+            let data = &state.objects[index];
+            let bytes = data.buffer.as_ref().expect("not an array buffer").bytes.as_ref();
+            assert!(offset + length <= bytes.map_or(0, Vec::len), "view out of range");
+            data.global
+        };
+        let mut object = ObjectData::new(class, global);
+        object.view = Some(ViewData {
+            buffer: index,
+            offset,
+            length,
+            element_type
+        });
+        let object = state.add_object(object);
+        Object::from_handle(Handle::new(&self.state, object))
+    }
+
+    /// Detaches the given `buffer`, as if by transferring it to another agent.
+    /// The views on the buffer remain, but no longer cover any bytes.
+    ///
+    /// # Panics
+    /// Panics if the given `buffer` is not an `ArrayBuffer`.
+    pub fn detach_array_buffer(&self, buffer: &Object<Reference>) {
+        let mut state = self.state.borrow_mut();
+        let data = state.objects[buffer.handle().index].buffer.as_mut().expect("not an array buffer");
+        assert!(!data.shared, "not an array buffer");
+        data.bytes = None;
+    }
+
+    /// Returns a new pending promise in the given `global`.
+    pub fn new_promise(&self, global: &Object<Reference>) -> Object<Reference> {
+        let promise = self.state.borrow_mut().add_promise(global.handle().index);
+        Object::from_handle(Handle::new(&self.state, promise))
+    }
+
+    /// Returns a new promise that depends on the given `promise`, as if by
+    /// calling `then` on it without any reactions. The new promise settles in
+    /// the same way as the given promise, at the same time.
+    ///
+    /// # Panics
+    /// Panics if the given `promise` is not a promise.
+    pub fn new_dependent_promise(&self, promise: &Object<Reference>) -> Object<Reference> {
+        let dependent = self.new_promise(&promise.global());
+        let mut state = self.state.borrow_mut();
+        let index = dependent.handle().index;
+        let (promise_state, result) = {
+            let data = state.objects[promise.handle().index].promise.as_mut().expect("not a promise");
+            data.dependents.push(index);
+            (data.state, data.result.clone())
+        };
+        if promise_state != PromiseState::Pending {
+            state.settle_promise(index, promise_state, result);
+        }
+        dependent
+    }
+
+    /// Fulfills the given `promise` with the given `value`, along with the
+    /// promises that depend on it. Does nothing if the promise is not pending.
+    pub fn resolve_promise(&self, promise: &Object<Reference>, value: &Value<Reference>) {
+        let mut state = self.state.borrow_mut();
+        state.settle_promise(promise.handle().index, PromiseState::Fulfilled, to_slot(value));
+    }
+
+    /// Rejects the given `promise` with the given `reason`, along with the
+    /// promises that depend on it. Does nothing if the promise is not pending.
+    pub fn reject_promise(&self, promise: &Object<Reference>, reason: &Value<Reference>) {
+        let mut state = self.state.borrow_mut();
+        state.settle_promise(promise.handle().index, PromiseState::Rejected, to_slot(reason));
+    }
+
+    /// Returns a new proxy for the given `target`, with the given `handler`,
+    /// in the global of the target. The runtime does not call the traps of
+    /// the handler: the interpreter forwards property accesses on the proxy to
+    /// its target, as if the handler had no traps.
+    pub fn new_proxy(&self, target: &Object<Reference>, handler: &Object<Reference>) -> Object<Reference> {
+        let mut state = self.state.borrow_mut();
+        let mut object = ObjectData::new("Proxy", state.objects[target.handle().index].global);
+        object.proxy = Some(ProxyData {
+            target: target.handle().index,
+            handler: handler.handle().index,
+            revoked: false
+        });
+        let object = state.add_object(object);
+        Object::from_handle(Handle::new(&self.state, object))
+    }
+
+    /// Revokes the given `proxy`. Does nothing if the given object is not a
+    /// proxy.
+    pub fn revoke_proxy(&self, proxy: &Object<Reference>) {
+        if let Some(ref mut proxy) = self.state.borrow_mut().objects[proxy.handle().index].proxy {
+            proxy.revoked = true;
+        }
+    }
+
+    /// Returns a new symbol with the given `description`, as if by calling
+    /// `Symbol`.
+    pub fn new_symbol(&self, description: Option<&str>) -> Symbol<Reference> {
+        let mut state = self.state.borrow_mut();
+        state.symbols.push(SymbolData {
+            description: description.map(|description| description.to_owned()),
+            registry_key: None
+        });
+        Symbol::from_handle(Handle::new(&self.state, state.symbols.len() - 1))
+    }
+
+    /// Returns the symbol registered under the given `key` in the global
+    /// symbol registry, as if by calling `Symbol.for`. If there is no such
+    /// symbol, registers a new one first.
+    pub fn registered_symbol(&self, key: &str) -> Symbol<Reference> {
+        let mut state = self.state.borrow_mut();
+        let index = match state.symbols.iter().position(|symbol| symbol.registry_key.as_deref() == Some(key)) {
+            Some(index) => index,
+            None => {
+                state.symbols.push(SymbolData {
+                    description: Some(key.to_owned()),
+                    registry_key: Some(key.to_owned())
+                });
+                state.symbols.len() - 1
+            }
+        };
+        Symbol::from_handle(Handle::new(&self.state, index))
+    }
+
+    /// Returns a new source, as described by the given `builder`.
+    pub fn new_source(&self, builder: SourceBuilder) -> Source<Reference> {
+        let mut state = self.state.borrow_mut();
+        let (element, element_attribute_name) = match builder.element {
+            Some((element, attribute_name)) => (Some(element.handle().index), attribute_name),
+            None => (None, None)
+        };
+        let (introduction_script, introduction_offset) = match builder.introduction_site {
+            Some((script, offset)) => (Some(script.handle().index), Some(offset)),
+            None => (None, None)
+        };
+        state.sources.push(SourceData {
+            url: builder.url,
+            text: builder.text,
+            source_map_url: builder.source_map_url,
+            element,
+            element_attribute_name,
+            introduction_type: builder.introduction_type,
+            introduction_script,
+            introduction_offset
+        });
+        Source::from_handle(Handle::new(&self.state, state.sources.len() - 1))
+    }
+
+    /// Returns a new top-level script in the given `global`, compiled from the
+    /// given `source`, as described by the given `builder`. Calls the new
+    /// script handlers of the debuggers observing the global.
+    ///
+    /// # Panics
+    /// Panics if an expression in the script is not valid, if a `yield` or
+    /// `await` appears outside of the body of a generator or async function,
+    /// respectively, or if a `LeaveWith` has no matching `EnterWith`.
+    pub fn new_script(&self, global: &Object<Reference>, source: &Source<Reference>,
+                      builder: ScriptBuilder) -> Script<Reference> {
+        let global = global.handle().index;
+        let (script, handlers) = {
+            let mut state = self.state.borrow_mut();
+            let script = add_script(&mut state, global, source.handle().index, builder);
+            (script, state.handlers(global, |debugger| &debugger.new_script_handler))
+        };
+        let script = Script::from_handle(Handle::new(&self.state, script));
+        for handler in handlers {
+            handler.handle(&script);
+        }
+        script
+    }
+
+    /// Executes the given top-level `script`. Returns a completion value
+    /// describing how the execution completed.
+    pub fn execute(&self, script: &Script<Reference>) -> CompletionValue<Reference> {
+        let completion = interpreter::execute(&self.state, script.handle().index);
+        to_completion_value(&self.state, &completion)
+    }
+
+    /// Resumes the suspended frame of the given `object`, which is either a
+    /// generator, or the promise returned by a call to an async function, and
+    /// runs it until it completes or is suspended again. Returns a completion
+    /// value describing how the frame was popped. The value of the `yield` or
+    /// `await` at which the frame was suspended is always `undefined`.
+    ///
+    /// Resuming a frame that is running throws a `TypeError`, and resuming a
+    /// frame that has completed returns `undefined`.
+    ///
+    /// # Panics
+    /// Panics if the given `object` is neither a generator nor the promise
+    /// returned by a call to an async function.
+    pub fn resume(&self, object: &Object<Reference>) -> CompletionValue<Reference> {
+        let frame = self.state.borrow().objects[object.handle().index].frame;
+        let completion = interpreter::resume(&self.state, frame.expect("not a generator or async function"));
+        to_completion_value(&self.state, &completion)
+    }
+
+    /// Marks the given `environment` as optimized out.
+    pub fn optimize_out(&self, environment: &Environment<Reference>) {
+        self.state.borrow_mut().environments[environment.handle().index].optimized_out = true;
+    }
+}
+
+fn add_script(state: &mut State, global: usize, source: usize, builder: ScriptBuilder) -> usize {
+    let children: Vec<usize> = builder.children.into_iter().map(|child| {
+        add_script(state, global, source, child)
+    }).collect();
+    let parse = |code: String| {
+        parser::parse(&code).unwrap_or_else(|message| {
+            panic!("invalid expression {:?}: {}", code, message)
+        })
+    };
+    let is_generator = matches!(builder.kind, FunctionKind::Generator | FunctionKind::AsyncGenerator);
+    let is_async = matches!(builder.kind, FunctionKind::Async | FunctionKind::AsyncArrow |
+                                          FunctionKind::AsyncGenerator);
+    let mut with_depth = 0;
+    let instructions = builder.steps.into_iter().map(|(line, column, step)| {
+        let operation = match step {
+            Step::Op(Op::Debugger) => Operation::Debugger,
+            Step::Op(Op::Expression(code)) => Operation::Expression(parse(code)),
+            Step::Op(Op::Var(name, code)) => Operation::Var(name, parse(code)),
+            Step::Op(Op::Return(code)) => Operation::Return(parse(code)),
+            Step::Op(Op::Throw(code)) => Operation::Throw(parse(code)),
+            Step::Op(Op::Yield(code)) => {
+                assert!(is_generator, "yield outside of a generator");
+                Operation::Yield(parse(code))
+            },
+            Step::Op(Op::Await(code)) => {
+                assert!(is_async, "await outside of an async function");
+                Operation::Await(parse(code))
+            },
+            Step::Op(Op::EnterWith(code)) => {
+                with_depth += 1;
+                Operation::EnterWith(parse(code))
+            },
+            Step::Op(Op::LeaveWith) => {
+                assert!(with_depth > 0, "leave with outside of a with statement");
+                with_depth -= 1;
+                Operation::LeaveWith
+            },
+            Step::Function(child) => Operation::Function(children[child])
+        };
+        Instruction {
+            line,
+            column,
+            operation
+        }
+    }).collect();
+    state.scripts.push(ScriptData {
+        global,
+        source,
+        source_start: builder.source_start,
+        source_length: builder.source_length,
+        start_line: builder.start_line,
+        display_name: builder.display_name,
+        name: builder.name,
+        parameters: builder.parameters,
+        kind: builder.kind,
+        children,
+        instructions,
+        breakpoints: Default::default()
+    });
+    state.scripts.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use {BreakpointHandler, CompletionValue, Debugger, DebuggerStatementHandler, EnterFrameHandler,
+         Environment, Error, ExceptionUnwindHandler, Frame, FunctionKind, NewScriptHandler, Object,
+         PopHandler, PromiseState, PropertyDescriptor, PropertyKey, ResumptionValue, Script, StepHandler,
+         Value};
+
+    use super::{Op, Reference, Runtime, ScriptBuilder, SourceBuilder};
+
+    /// A handler of every kind that records each call in a shared log, and
+    /// returns a fixed resumption value.
+    #[derive(Clone)]
+    struct Recorder {
+        log: Rc<RefCell<Vec<String>>>,
+        resumption: ResumptionValue<Reference>
+    }
+
+    impl Recorder {
+        fn new() -> Recorder {
+            Recorder {
+                log: Rc::new(RefCell::new(Vec::new())),
+                resumption: None
+            }
+        }
+
+        fn resuming(&self, resumption: CompletionValue<Reference>) -> Recorder {
+            Recorder {
+                log: self.log.clone(),
+                resumption: Some(resumption)
+            }
+        }
+
+        fn record(&self, entry: String) -> ResumptionValue<Reference> {
+            self.log.borrow_mut().push(entry);
+            self.resumption.clone()
+        }
+
+        fn log(&self) -> Vec<String> {
+            self.log.borrow().clone()
+        }
+    }
+
+    fn describe(frame: &Frame<Reference>) -> String {
+        let name = frame.callee().and_then(|callee| callee.name()).unwrap_or_else(|| "global".to_owned());
+        format!("{}@{}", name, frame.offset().unwrap())
+    }
+
+    impl BreakpointHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("breakpoint {}", describe(frame)))
+        }
+    }
+
+    impl DebuggerStatementHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("debugger {}", describe(frame)))
+        }
+    }
+
+    impl EnterFrameHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("enter {}", describe(frame)))
+        }
+    }
+
+    impl ExceptionUnwindHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>, exception: &Value<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("unwind {} {:?}", describe(frame), exception))
+        }
+    }
+
+    impl NewScriptHandler<Reference> for Recorder {
+        fn handle(&self, script: &Script<Reference>) {
+            self.record(format!("new script {}", script.url()));
+        }
+    }
+
+    impl PopHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>, completion: &CompletionValue<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("pop {} {:?}", describe(frame), completion))
+        }
+    }
+
+    impl StepHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("step {}", describe(frame)))
+        }
+    }
+
+    /// A breakpoint handler that sets the step and pop handlers of the frame
+    /// that hit the breakpoint.
+    struct Watcher(Recorder);
+
+    impl BreakpointHandler<Reference> for Watcher {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            frame.set_step_handler(Some(Rc::new(Box::new(self.0.clone()))));
+            frame.set_pop_handler(Some(Rc::new(Box::new(self.0.clone()))));
+            None
+        }
+    }
+
+    /// A breakpoint handler that keeps the environment of the frame that hit
+    /// the breakpoint.
+    #[derive(Clone, Default)]
+    struct Inspector(Rc<RefCell<Option<Environment<Reference>>>>);
+
+    impl BreakpointHandler<Reference> for Inspector {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            *self.0.borrow_mut() = frame.environment();
+            None
+        }
+    }
+
+    /// A breakpoint handler that calls a function with the frame that hit the
+    /// breakpoint.
+    struct Hook<F: Fn(&Frame<Reference>)>(F);
+
+    impl<F: Fn(&Frame<Reference>)> BreakpointHandler<Reference> for Hook<F> {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            (self.0)(frame);
+            None
+        }
+    }
+
+    fn setup() -> (Runtime, Object<Reference>, Debugger<Reference>) {
+        let runtime = Runtime::new();
+        let global = runtime.new_global();
+        let debugger = runtime.new_debugger();
+        debugger.add_debuggee(&global);
+        (runtime, global, debugger)
+    }
+
+    fn new_script(runtime: &Runtime, global: &Object<Reference>, builder: ScriptBuilder) -> Script<Reference> {
+        let source = runtime.new_source(SourceBuilder::new("test.js", ""));
+        runtime.new_script(global, &source, builder)
+    }
+
+    /// Returns a script that defines a function `f` that returns its argument
+    /// plus one, and then calls it.
+    fn call_script() -> ScriptBuilder {
+        ScriptBuilder::new(1)
+            .function(1, "f", &["a"], ScriptBuilder::new(2)
+                .op(2, Op::Var("b".to_owned(), "a + 1".to_owned()))
+                .op(3, Op::Return("b".to_owned())))
+            .op(5, Op::Var("x".to_owned(), "f(1)".to_owned()))
+            .op(6, Op::Expression("x".to_owned()))
+    }
+
+    /// Returns the object that is the value of the variable with the given
+    /// `name` in the given `global`.
+    fn variable(global: &Object<Reference>, name: &str) -> Object<Reference> {
+        match global.as_environment().unwrap().get_variable(name).unwrap() {
+            Value::Object(object) => object,
+            value => panic!("{} is {:?}", name, value)
+        }
+    }
+
+    fn number(value: f64) -> Value<Reference> {
+        Value::Number(value)
+    }
+
+    #[test]
+    fn step_and_pop_handlers() {
+        let (runtime, global, _debugger) = setup();
+        let recorder = Recorder::new();
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .op(1, Op::Debugger)
+            .op(2, Op::Var("x".to_owned(), "1".to_owned()))
+            .op(3, Op::Return("x".to_owned())));
+        script.set_breakpoint(0, Rc::new(Box::new(Watcher(recorder.clone())))).unwrap();
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(number(1.0)));
+        assert_eq!(recorder.log(), vec![
+            "step global@1",
+            "step global@2",
+            "pop global@2 Return(Number(1.0))"
+        ]);
+    }
+
+    #[test]
+    fn pop_handler_resumption_value_replaces_the_completion() {
+        let (runtime, global, _debugger) = setup();
+        let recorder = Recorder::new().resuming(CompletionValue::Return(number(2.0)));
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .op(1, Op::Debugger)
+            .op(2, Op::Throw("1".to_owned())));
+        script.set_breakpoint(0, Rc::new(Box::new(Watcher(recorder.clone())))).unwrap();
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(number(2.0)));
+    }
+
+    #[test]
+    fn breakpoint_handlers() {
+        let (runtime, global, _debugger) = setup();
+        let recorder = Recorder::new();
+        let script = new_script(&runtime, &global, call_script());
+        let function = script.get_child_scripts()[0].clone();
+        function.set_breakpoint(1, Rc::new(Box::new(recorder.clone()))).unwrap();
+        script.set_breakpoint(2, Rc::new(Box::new(recorder.clone()))).unwrap();
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(Value::Undefined));
+        assert_eq!(recorder.log(), vec!["breakpoint f@1", "breakpoint global@2"]);
+    }
+
+    #[test]
+    fn breakpoint_handler_resumption_value_completes_the_frame() {
+        let (runtime, global, _debugger) = setup();
+        let recorder = Recorder::new().resuming(CompletionValue::Return(number(5.0)));
+        let script = new_script(&runtime, &global, call_script());
+        script.get_child_scripts()[0].set_breakpoint(0, Rc::new(Box::new(recorder.clone()))).unwrap();
+        runtime.execute(&script);
+        let environment = global.as_environment().unwrap();
+        assert_eq!(environment.get_variable("x"), Ok(number(5.0)));
+    }
+
+    #[test]
+    fn breakpoints_by_id() {
+        let (runtime, global, _debugger) = setup();
+        let (first, second) = (Recorder::new(), Recorder::new());
+        let script = new_script(&runtime, &global, call_script());
+        let function = script.get_child_scripts()[0].clone();
+        let first_id = function.set_breakpoint(1, Rc::new(Box::new(first.clone()))).unwrap();
+        let second_id = function.set_breakpoint(1, Rc::new(Box::new(second.clone()))).unwrap();
+        let ids = |script: &Script<Reference>| -> Vec<_> {
+            let breakpoints = script.get_breakpoints(1).unwrap();
+            breakpoints.into_iter().map(|breakpoint| (breakpoint.id, breakpoint.enabled)).collect()
+        };
+        assert_eq!(ids(&function), vec![(first_id, true), (second_id, true)]);
+        function.set_breakpoint_enabled(second_id, false).unwrap();
+        assert_eq!(ids(&function), vec![(first_id, true), (second_id, false)]);
+        runtime.execute(&script);
+        assert_eq!((first.log().len(), second.log().len()), (1, 0));
+        function.set_breakpoint_enabled(second_id, true).unwrap();
+        function.remove_breakpoint(first_id).unwrap();
+        assert_eq!(ids(&function), vec![(second_id, true)]);
+        runtime.execute(&script);
+        assert_eq!((first.log().len(), second.log().len()), (1, 1));
+        assert_eq!(function.remove_breakpoint(first_id), Err(Error::BreakpointNotFound));
+        assert_eq!(script.remove_breakpoint(second_id), Err(Error::BreakpointNotFound));
+        assert_eq!(function.set_breakpoint_enabled(first_id, true), Err(Error::BreakpointNotFound));
+    }
+
+    #[test]
+    fn get_all_breakpoints_is_ordered_by_id() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let script = new_script(&runtime, &global, call_script());
+        let function = script.get_child_scripts()[0].clone();
+        let other = runtime.new_global();
+        new_script(&runtime, &other, call_script()).set_breakpoint(0, Rc::new(Box::new(recorder.clone()))).unwrap();
+        function.set_breakpoint(1, Rc::new(Box::new(recorder.clone()))).unwrap();
+        script.set_breakpoint(2, Rc::new(Box::new(recorder.clone()))).unwrap();
+        let removed = function.set_breakpoint(0, Rc::new(Box::new(recorder.clone()))).unwrap();
+        script.set_breakpoint(1, Rc::new(Box::new(recorder.clone()))).unwrap();
+        function.remove_breakpoint(removed).unwrap();
+        let breakpoints: Vec<_> = debugger.get_all_breakpoints().into_iter().map(|breakpoint| {
+            (breakpoint.id.0, breakpoint.script == function, breakpoint.offset, breakpoint.line)
+        }).collect();
+        assert_eq!(breakpoints, vec![(1, true, 1, 3), (2, false, 2, 6), (4, false, 1, 5)]);
+    }
+
+    #[test]
+    fn with_statements() {
+        let (runtime, global, _debugger) = setup();
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .function(1, "o", &[], ScriptBuilder::new(1))
+            .op(1, Op::Expression("o.p = 1".to_owned()))
+            .op(2, Op::EnterWith("o".to_owned()))
+            .op(3, Op::Var("x".to_owned(), "p".to_owned()))
+            .op(4, Op::LeaveWith)
+            .op(5, Op::Return("x".to_owned())));
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(number(1.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "leave with outside of a with statement")]
+    fn leave_with_without_enter_with() {
+        let (runtime, global, _debugger) = setup();
+        new_script(&runtime, &global, ScriptBuilder::new(1)
+            .op(1, Op::EnterWith("this".to_owned()))
+            .op(2, Op::LeaveWith)
+            .op(3, Op::LeaveWith));
+    }
+
+
+    #[test]
+    fn debugger_statement_handler() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .op(1, Op::Var("x".to_owned(), "1".to_owned()))
+            .op(2, Op::Debugger)
+            .op(3, Op::Return("x".to_owned())));
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(number(1.0)));
+        assert_eq!(recorder.log(), vec!["debugger global@1"]);
+    }
+
+    #[test]
+    fn debugger_statement_handler_resumption_value_completes_the_frame() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new().resuming(CompletionValue::Return(number(7.0)));
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .op(1, Op::Debugger)
+            .op(2, Op::Var("x".to_owned(), "1".to_owned())));
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(number(7.0)));
+        let environment = global.as_environment().unwrap();
+        assert_eq!(environment.get_variable("x"), Ok(Value::Undefined));
+    }
+
+    #[test]
+    fn enter_frame_handler() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        debugger.set_enter_frame_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        let script = new_script(&runtime, &global, call_script());
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(Value::Undefined));
+        assert_eq!(recorder.log(), vec!["enter global@0", "enter f@0"]);
+        let environment = global.as_environment().unwrap();
+        assert_eq!(environment.get_variable("x"), Ok(number(2.0)));
+    }
+
+    #[test]
+    fn exception_unwind_handler() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        debugger.set_exception_unwind_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .function(1, "f", &[], ScriptBuilder::new(2).op(2, Op::Throw("'oops'".to_owned())))
+            .op(4, Op::Expression("f()".to_owned())));
+        let exception = Value::String("oops".to_owned());
+        assert_eq!(runtime.execute(&script), CompletionValue::Throw(exception.clone()));
+        assert_eq!(recorder.log(), vec![
+            format!("unwind f@0 {:?}", exception),
+            format!("unwind global@1 {:?}", exception)
+        ]);
+    }
+
+    #[test]
+    fn exception_unwind_handler_resumption_value_completes_the_frame() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new().resuming(CompletionValue::Return(number(3.0)));
+        debugger.set_exception_unwind_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .function(1, "f", &[], ScriptBuilder::new(2).op(2, Op::Throw("'oops'".to_owned())))
+            .op(4, Op::Var("x".to_owned(), "f()".to_owned())));
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(Value::Undefined));
+        assert_eq!(recorder.log().len(), 1);
+        let environment = global.as_environment().unwrap();
+        assert_eq!(environment.get_variable("x"), Ok(number(3.0)));
+    }
+
+    #[test]
+    fn new_script_handler() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        debugger.set_new_script_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        new_script(&runtime, &global, call_script());
+        let other = runtime.new_global();
+        new_script(&runtime, &other, call_script());
+        assert_eq!(recorder.log(), vec!["new script test.js"]);
+    }
+
+    #[test]
+    fn handlers_are_only_called_for_debuggees_of_enabled_debuggers() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        debugger.set_enter_frame_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        let builder = || ScriptBuilder::new(1).op(1, Op::Debugger);
+        let other = runtime.new_global();
+        let script = new_script(&runtime, &other, builder());
+        script.set_breakpoint(0, Rc::new(Box::new(recorder.clone()))).unwrap();
+        runtime.execute(&script);
+        debugger.set_enabled(false);
+        let script = new_script(&runtime, &global, builder());
+        script.set_breakpoint(0, Rc::new(Box::new(recorder.clone()))).unwrap();
+        runtime.execute(&script);
+        assert!(recorder.log().is_empty());
+        debugger.set_enabled(true);
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec!["enter global@0", "breakpoint global@0", "debugger global@0"]);
+    }
+
+    #[test]
+    fn with_proxy_environment_would_run_the_debuggee() {
+        let (runtime, global, _debugger) = setup();
+        let target = runtime.new_object(&global, "Object");
+        let proxy = runtime.new_proxy(&target, &runtime.new_object(&global, "Object"));
+        let descriptor = PropertyDescriptor::data(Value::Object(proxy)).with_writable(true);
+        global.define_property(&PropertyKey::from("p"), &descriptor).unwrap();
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .op(1, Op::EnterWith("p".to_owned()))
+            .op(2, Op::Debugger)
+            .op(3, Op::LeaveWith));
+        let inspector = Inspector::default();
+        script.set_breakpoint(1, Rc::new(Box::new(inspector.clone()))).unwrap();
+        runtime.execute(&script);
+        let environment = inspector.0.borrow().clone().unwrap();
+        assert!(environment.object().unwrap().unwrap().is_proxy());
+        assert_eq!(environment.names(), Err(Error::DebuggeeWouldRun));
+        assert_eq!(environment.get_variable("x"), Err(Error::DebuggeeWouldRun));
+        assert_eq!(environment.set_variable("x", &Value::Null), Err(Error::DebuggeeWouldRun));
+    }
+
+    #[test]
+    fn own_property_keys_are_ordered() {
+        let (runtime, global, _debugger) = setup();
+        let object = runtime.new_object(&global, "Object");
+        let (first, second) = (runtime.new_symbol(Some("first")), runtime.new_symbol(None));
+        let keys = vec![
+            PropertyKey::from("b"),
+            PropertyKey::from(second.clone()),
+            PropertyKey::from(10),
+            PropertyKey::from("a"),
+            PropertyKey::from(first.clone()),
+            PropertyKey::from("2")
+        ];
+        for key in &keys {
+            object.define_property(key, &PropertyDescriptor::data(Value::Null)).unwrap();
+        }
+        assert_eq!(object.get_own_property_names(), Ok(vec![
+            PropertyKey::from(2),
+            PropertyKey::from(10),
+            PropertyKey::from("b"),
+            PropertyKey::from("a")
+        ]));
+        assert_eq!(object.get_own_property_symbols(), Ok(vec![second, first]));
+    }
+
+    #[test]
+    fn collection_entries_are_paged() {
+        let (runtime, global, _debugger) = setup();
+        let map = runtime.new_map(&global, &[
+            (number(1.0), number(2.0)),
+            (number(3.0), number(4.0)),
+            (number(5.0), number(6.0))
+        ]);
+        assert_eq!(map.collection_size(), Some(3));
+        assert_eq!(map.map_entries(1..3), Some(vec![(number(3.0), number(4.0)), (number(5.0), number(6.0))]));
+        assert_eq!(map.map_entries(2..10), Some(vec![(number(5.0), number(6.0))]));
+        assert_eq!(map.map_entries(5..10), Some(Vec::new()));
+        let (start, end) = (2, 1);
+        assert_eq!(map.map_entries(start..end), Some(Vec::new()));
+        assert_eq!(map.set_entries(0..1), None);
+        assert_eq!(map.weak_entries(0..1), None);
+
+        let set = runtime.new_set(&global, &[number(1.0), number(2.0)]);
+        assert_eq!(set.collection_size(), Some(2));
+        assert_eq!(set.set_entries(1..5), Some(vec![number(2.0)]));
+        assert_eq!(set.map_entries(0..1), None);
+
+        let (a, b) = (runtime.new_object(&global, "Object"), runtime.new_object(&global, "Object"));
+        let weak_map = runtime.new_weak_map(&global, &[(a.clone(), number(1.0)), (b.clone(), number(2.0))]);
+        assert_eq!(weak_map.weak_entries(1..2), Some(vec![(b.clone(), Some(number(2.0)))]));
+        assert_eq!(weak_map.weak_entries(2..3), Some(Vec::new()));
+        let weak_set = runtime.new_weak_set(&global, &[a.clone(), b]);
+        assert_eq!(weak_set.weak_entries(0..1), Some(vec![(a.clone(), None)]));
+        assert_eq!(weak_set.set_entries(0..1), None);
+
+        assert_eq!(a.collection_size(), None);
+        assert_eq!(a.map_entries(0..1), None);
+        assert_eq!(a.weak_entries(0..1), None);
+    }
+
+    #[test]
+    fn bytes_of_views() {
+        let (runtime, global, _debugger) = setup();
+        let buffer = runtime.new_array_buffer(&global, &[0, 1, 2, 3, 4, 5, 6, 7]);
+        let view = runtime.new_data_view(&buffer, 2, 4);
+        assert_eq!(view.byte_length(), Some(4));
+        assert_eq!(view.read_bytes(0..2), Some(vec![2, 3]));
+        assert_eq!(view.read_bytes(3..10), Some(vec![5]));
+        assert_eq!(view.read_bytes(4..10), Some(Vec::new()));
+        assert_eq!(view.write_bytes(1, &[9, 9]), Ok(()));
+        assert_eq!(buffer.read_bytes(0..8), Some(vec![0, 1, 2, 9, 9, 5, 6, 7]));
+        assert_eq!(view.write_bytes(3, &[1, 1]), Err(Error::RangeNotValid));
+        assert_eq!(view.write_bytes(usize::MAX, &[1]), Err(Error::RangeNotValid));
+        assert_eq!(buffer.read_bytes(0..8), Some(vec![0, 1, 2, 9, 9, 5, 6, 7]));
+
+        let object = runtime.new_object(&global, "Object");
+        assert_eq!(object.read_bytes(0..1), None);
+        assert_eq!(object.write_bytes(0, &[1]), Err(Error::ObjectNotBinaryData));
+
+        runtime.detach_array_buffer(&buffer);
+        assert!(view.is_detached());
+        assert_eq!(view.byte_length(), Some(0));
+        assert_eq!(view.read_bytes(0..4), Some(Vec::new()));
+        assert_eq!(view.write_bytes(0, &[1]), Err(Error::BufferDetached));
+    }
+
+    #[test]
+    fn function_location_is_relative_to_the_source() {
+        let (runtime, global, _debugger) = setup();
+        // An inline script, whose source starts at line 10 of its document.
+        let text = "\n    function f(a) {\n      return a;\n    }\n    f(1);\n";
+        let source = runtime.new_source(SourceBuilder::new("test.html", text));
+        let script = runtime.new_script(&global, &source, ScriptBuilder::new(10)
+            .function(11, "f", &["a"], ScriptBuilder::new(11)
+                .source_range(5, 37)
+                .op(12, Op::Return("a".to_owned())))
+            .op(14, Op::Expression("f(1)".to_owned())));
+        runtime.execute(&script);
+        let function = global.as_environment().unwrap().get_variable("f").unwrap();
+        let function = match function {
+            Value::Object(function) => function,
+            _ => panic!("f is not an object")
+        };
+        assert_eq!(function.function_source_text().unwrap(), "function f(a) {\n      return a;\n    }");
+        let location = function.function_location().unwrap();
+        assert_eq!(location.source, source);
+        assert_eq!((location.start_line, location.start_column), (2, 4));
+        assert_eq!((location.end_line, location.end_column), (4, 5));
+    }
+
+    #[test]
+    fn closure_scopes() {
+        let (runtime, global, _debugger) = setup();
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .function(1, "f", &["a"], ScriptBuilder::new(1)
+                .function(2, "g", &[], ScriptBuilder::new(2)
+                    .op(2, Op::Return("a".to_owned())))
+                .op(3, Op::Return("g".to_owned())))
+            .op(5, Op::Var("h".to_owned(), "f(1)".to_owned())));
+        runtime.execute(&script);
+        let h = match global.as_environment().unwrap().get_variable("h").unwrap() {
+            Value::Object(h) => h,
+            _ => panic!("h is not an object")
+        };
+        let scopes = h.closure_scopes().unwrap().unwrap();
+        assert_eq!(scopes.len(), 1);
+        assert!(!scopes[0].optimized_out);
+        assert!(scopes[0].bindings.contains(&("a".to_owned(), Some(number(1.0)))));
+
+        runtime.optimize_out(&h.environment().unwrap());
+        let scopes = h.closure_scopes().unwrap().unwrap();
+        assert_eq!(scopes.len(), 1);
+        assert!(scopes[0].optimized_out);
+        assert!(scopes[0].bindings.contains(&("a".to_owned(), None)));
+        assert!(global.closure_scopes().unwrap().is_none());
+    }
+
+    #[test]
+    fn generator_frames_yield_and_resume() {
+        let (runtime, global, _debugger) = setup();
+        let recorder = Recorder::new();
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .function(1, "g", &[], ScriptBuilder::new(1)
+                .kind(FunctionKind::Generator)
+                .op(2, Op::Yield("1".to_owned()))
+                .op(3, Op::Yield("2".to_owned()))
+                .op(4, Op::Return("3".to_owned())))
+            .op(6, Op::Var("it".to_owned(), "g()".to_owned())));
+        let frames = Rc::new(RefCell::new(Vec::new()));
+        let (handler, entered) = (recorder.clone(), frames.clone());
+        script.get_child_scripts()[0].set_breakpoint(0, Rc::new(Box::new(Hook(move |frame: &Frame<Reference>| {
+            frame.set_pop_handler(Some(Rc::new(Box::new(handler.clone()))));
+            entered.borrow_mut().push(frame.clone());
+        })))).unwrap();
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(Value::Undefined));
+        assert!(frames.borrow().is_empty());
+        let generator = variable(&global, "it");
+        assert_eq!(runtime.resume(&generator), CompletionValue::Yield(number(1.0)));
+        let frame = frames.borrow()[0].clone();
+        assert!(frame.is_suspended());
+        assert_eq!(runtime.resume(&generator), CompletionValue::Yield(number(2.0)));
+        assert_eq!(runtime.resume(&generator), CompletionValue::Return(number(3.0)));
+        assert!(!frame.is_suspended());
+        assert_eq!(runtime.resume(&generator), CompletionValue::Return(Value::Undefined));
+        assert_eq!(frames.borrow().len(), 1);
+        assert_eq!(recorder.log(), vec![
+            "pop g@1 Yield(Number(1.0))",
+            "pop g@2 Yield(Number(2.0))",
+            "pop g@2 Return(Number(3.0))"
+        ]);
+    }
+
+    #[test]
+    fn resuming_a_running_frame_throws() {
+        let runtime = Rc::new(Runtime::new());
+        let global = runtime.new_global();
+        let debugger = runtime.new_debugger();
+        debugger.add_debuggee(&global);
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .function(1, "g", &[], ScriptBuilder::new(1)
+                .kind(FunctionKind::Generator)
+                .op(2, Op::Yield("1".to_owned())))
+            .op(4, Op::Var("it".to_owned(), "g()".to_owned())));
+        runtime.execute(&script);
+        let generator = variable(&global, "it");
+        let completions = Rc::new(RefCell::new(Vec::new()));
+        let (resumer, resumed, inner) = (runtime.clone(), generator.clone(), completions.clone());
+        script.get_child_scripts()[0].set_breakpoint(0, Rc::new(Box::new(Hook(move |_: &Frame<Reference>| {
+            inner.borrow_mut().push(resumer.resume(&resumed));
+        })))).unwrap();
+        assert_eq!(runtime.resume(&generator), CompletionValue::Yield(number(1.0)));
+        let exception = Value::String("TypeError: already running".to_owned());
+        assert_eq!(*completions.borrow(), vec![CompletionValue::Throw(exception)]);
+    }
+
+    #[test]
+    fn async_frames_await_and_resume() {
+        let (runtime, global, _debugger) = setup();
+        let recorder = Recorder::new();
+        let script = new_script(&runtime, &global, ScriptBuilder::new(1)
+            .function(1, "a", &[], ScriptBuilder::new(1)
+                .kind(FunctionKind::Async)
+                .op(2, Op::Await("1".to_owned()))
+                .op(3, Op::Return("2".to_owned())))
+            .op(5, Op::Var("p".to_owned(), "a()".to_owned())));
+        let handler = recorder.clone();
+        script.get_child_scripts()[0].set_breakpoint(0, Rc::new(Box::new(Hook(move |frame: &Frame<Reference>| {
+            frame.set_pop_handler(Some(Rc::new(Box::new(handler.clone()))));
+        })))).unwrap();
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec!["pop a@1 Await(Number(1.0))"]);
+        let promise = variable(&global, "p");
+        assert_eq!(promise.promise_state(), Some(PromiseState::Pending));
+        assert_eq!(runtime.resume(&promise), CompletionValue::Return(number(2.0)));
+        assert_eq!(promise.promise_state(), Some(PromiseState::Fulfilled));
+        assert_eq!(promise.promise_value(), Some(number(2.0)));
+        assert_eq!(recorder.log(), vec!["pop a@1 Await(Number(1.0))", "pop a@1 Return(Number(2.0))"]);
+    }
+}
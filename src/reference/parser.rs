@@ -0,0 +1,416 @@
+//! A parser for the expression language understood by the simulated debuggee.
+//!
+//! The language is a small subset of JavaScript expressions: literals,
+//! identifiers, `this`, member accesses, calls, assignments, the unary
+//! operators `!` and `-`, and the binary operators `+`, `-`, `<`, `<=`, `>`,
+//! `>=`, `==`, `!=`, `===`, `!==`, `&&` and `||`.
+
+use BigInt;
+
+use super::state::{Key, Slot};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnaryOperator {
+    Not,
+    Negate
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BinaryOperator {
+    Add,
+    Subtract,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+    And,
+    Or
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    Literal(Slot),
+    Identifier(String),
+    This,
+    Member(Box<Expression>, Key),
+    Call(Box<Expression>, Vec<Expression>),
+    Assign(Box<Expression>, Box<Expression>),
+    Unary(UnaryOperator, Box<Expression>),
+    Binary(BinaryOperator, Box<Expression>, Box<Expression>)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    Number(f64),
+    BigInt(BigInt),
+    String(String),
+    Punctuator(&'static str)
+}
+
+const PUNCTUATORS: [&str; 20] = [
+    "===", "!==", "==", "!=", "<=", ">=", "&&", "||",
+    "(", ")", ",", ".", "=", "<", ">", "+", "-", "!", "[", "]"
+];
+
+fn tokenize(code: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = code;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                          .unwrap_or(rest.len());
+            tokens.push(Token::Identifier(rest[..end].to_owned()));
+            rest = &rest[end..];
+        } else if c.is_ascii_digit() {
+            let end = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                          .unwrap_or(rest.len());
+            if rest[end..].starts_with('n') {
+                tokens.push(Token::BigInt(parse_bigint(&rest[..end])?));
+                rest = &rest[end + 1..];
+                continue;
+            }
+            let number = rest[..end].parse().map_err(|_| {
+                format!("invalid number {}", &rest[..end])
+            })?;
+            tokens.push(Token::Number(number));
+            rest = &rest[end..];
+        } else if c == '"' || c == '\'' {
+            let end = rest[1..].find(c).ok_or_else(|| "unterminated string".to_owned())?;
+            tokens.push(Token::String(rest[1..end + 1].to_owned()));
+            rest = &rest[end + 2..];
+        } else {
+            let punctuator = PUNCTUATORS.iter().find(|&&punctuator| {
+                rest.starts_with(punctuator)
+            }).ok_or_else(|| format!("unexpected character {}", c))?;
+            tokens.push(Token::Punctuator(punctuator));
+            rest = &rest[punctuator.len()..];
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parses the given decimal `digits` as the magnitude of a BigInt.
+fn parse_bigint(digits: &str) -> Result<BigInt, String> {
+    let mut magnitude: Vec<u32> = Vec::new();
+    for c in digits.chars() {
+        let mut carry = u64::from(c.to_digit(10).ok_or_else(|| {
+            format!("invalid BigInt {}n", digits)
+        })?);
+        for digit in &mut magnitude {
+            let value = u64::from(*digit) * 10 + carry;
+            *digit = value as u32;
+            carry = value >> 32;
+        }
+        if carry != 0 {
+            magnitude.push(carry as u32);
+        }
+    }
+    Ok(BigInt::new(false, magnitude))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, punctuator: &str) -> bool {
+        match self.peek() {
+            Some(&Token::Punctuator(token)) if token == punctuator => {
+                self.position += 1;
+                true
+            },
+            _ => false
+        }
+    }
+
+    fn expect(&mut self, punctuator: &str) -> Result<(), String> {
+        if self.eat(punctuator) {
+            Ok(())
+        } else {
+            Err(format!("expected {}", punctuator))
+        }
+    }
+
+    fn assignment(&mut self) -> Result<Expression, String> {
+        let target = self.binary(0)?;
+        if self.eat("=") {
+            match target {
+                Expression::Identifier(_) | Expression::Member(_, _) => {
+                    let value = self.assignment()?;
+                    Ok(Expression::Assign(Box::new(target), Box::new(value)))
+                },
+                _ => Err("invalid assignment target".to_owned())
+            }
+        } else {
+            Ok(target)
+        }
+    }
+
+    fn binary_operator(&self, level: usize) -> Option<BinaryOperator> {
+        let punctuator = match self.peek() {
+            Some(&Token::Punctuator(punctuator)) => punctuator,
+            _ => return None
+        };
+        let operator = match punctuator {
+            "||" => BinaryOperator::Or,
+            "&&" => BinaryOperator::And,
+            "==" | "===" => BinaryOperator::Equal,
+            "!=" | "!==" => BinaryOperator::NotEqual,
+            "<" => BinaryOperator::Less,
+            "<=" => BinaryOperator::LessOrEqual,
+            ">" => BinaryOperator::Greater,
+            ">=" => BinaryOperator::GreaterOrEqual,
+            "+" => BinaryOperator::Add,
+            "-" => BinaryOperator::Subtract,
+            _ => return None
+        };
+        if precedence(operator) == level {
+            Some(operator)
+        } else {
+            None
+        }
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expression, String> {
+        if level == 5 {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        while let Some(operator) = self.binary_operator(level) {
+            self.position += 1;
+            let right = self.binary(level + 1)?;
+            left = Expression::Binary(operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expression, String> {
+        if self.eat("!") {
+            Ok(Expression::Unary(UnaryOperator::Not, Box::new(self.unary()?)))
+        } else if self.eat("-") {
+            Ok(Expression::Unary(UnaryOperator::Negate, Box::new(self.unary()?)))
+        } else {
+            self.postfix()
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Expression, String> {
+        let mut expression = self.primary()?;
+        loop {
+            if self.eat(".") {
+                match self.next() {
+                    Some(Token::Identifier(name)) => {
+                        expression = Expression::Member(Box::new(expression), Key::String(name));
+                    },
+                    _ => return Err("expected property name".to_owned())
+                }
+            } else if self.eat("[") {
+                let key = match self.next() {
+                    Some(Token::String(name)) => Key::from_name(&name),
+                    Some(Token::Number(index)) => Key::from_name(&format!("{}", index)),
+                    _ => return Err("expected property name".to_owned())
+                };
+                self.expect("]")?;
+                expression = Expression::Member(Box::new(expression), key);
+            } else if self.eat("(") {
+                let mut arguments = Vec::new();
+                if !self.eat(")") {
+                    loop {
+                        arguments.push(self.assignment()?);
+                        if self.eat(")") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                expression = Expression::Call(Box::new(expression), arguments);
+            } else {
+                return Ok(expression);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Expression::Literal(Slot::Number(number))),
+            Some(Token::BigInt(value)) => Ok(Expression::Literal(Slot::BigInt(value))),
+            Some(Token::String(string)) => Ok(Expression::Literal(Slot::String(string))),
+            Some(Token::Identifier(name)) => Ok(match name.as_str() {
+                "undefined" => Expression::Literal(Slot::Undefined),
+                "null" => Expression::Literal(Slot::Null),
+                "true" => Expression::Literal(Slot::Boolean(true)),
+                "false" => Expression::Literal(Slot::Boolean(false)),
+                "this" => Expression::This,
+                _ => Expression::Identifier(name)
+            }),
+            Some(Token::Punctuator("(")) => {
+                let expression = self.assignment()?;
+                self.expect(")")?;
+                Ok(expression)
+            },
+            Some(token) => Err(format!("unexpected token {:?}", token)),
+            None => Err("unexpected end of input".to_owned())
+        }
+    }
+}
+
+fn precedence(operator: BinaryOperator) -> usize {
+    match operator {
+        BinaryOperator::Or => 0,
+        BinaryOperator::And => 1,
+        BinaryOperator::Equal | BinaryOperator::NotEqual => 2,
+        BinaryOperator::Less | BinaryOperator::LessOrEqual |
+        BinaryOperator::Greater | BinaryOperator::GreaterOrEqual => 3,
+        BinaryOperator::Add | BinaryOperator::Subtract => 4
+    }
+}
+
+/// Parses the given `code` as an expression. If the code is not a valid
+/// expression, returns a message describing the syntax error instead.
+pub fn parse(code: &str) -> Result<Expression, String> {
+    let mut parser = Parser {
+        tokens: tokenize(code)?,
+        position: 0
+    };
+    let expression = parser.assignment()?;
+    match parser.peek() {
+        None => Ok(expression),
+        Some(token) => Err(format!("unexpected token {:?}", token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use BigInt;
+
+    use super::{BinaryOperator, Expression, UnaryOperator, parse};
+    use super::super::state::{Key, Slot};
+
+    fn identifier(name: &str) -> Box<Expression> {
+        Box::new(Expression::Identifier(name.to_owned()))
+    }
+
+    fn number(value: f64) -> Box<Expression> {
+        Box::new(Expression::Literal(Slot::Number(value)))
+    }
+
+    fn binary(operator: BinaryOperator, left: Box<Expression>, right: Box<Expression>) -> Box<Expression> {
+        Box::new(Expression::Binary(operator, left, right))
+    }
+
+    #[test]
+    fn binary_operators_bind_by_precedence() {
+        assert_eq!(parse("a || b && c == d < e + f"), Ok(*binary(
+            BinaryOperator::Or,
+            identifier("a"),
+            binary(BinaryOperator::And, identifier("b"), binary(
+                BinaryOperator::Equal,
+                identifier("c"),
+                binary(BinaryOperator::Less, identifier("d"), binary(
+                    BinaryOperator::Add,
+                    identifier("e"),
+                    identifier("f")
+                ))
+            ))
+        )));
+    }
+
+    #[test]
+    fn binary_operators_associate_to_the_left() {
+        assert_eq!(parse("1 - 2 - 3"), Ok(*binary(
+            BinaryOperator::Subtract,
+            binary(BinaryOperator::Subtract, number(1.0), number(2.0)),
+            number(3.0)
+        )));
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(parse("(a || b) && c"), Ok(*binary(
+            BinaryOperator::And,
+            binary(BinaryOperator::Or, identifier("a"), identifier("b")),
+            identifier("c")
+        )));
+    }
+
+    #[test]
+    fn unary_operators_bind_tighter_than_binary_operators() {
+        assert_eq!(parse("!a == -1"), Ok(*binary(
+            BinaryOperator::Equal,
+            Box::new(Expression::Unary(UnaryOperator::Not, identifier("a"))),
+            Box::new(Expression::Unary(UnaryOperator::Negate, number(1.0)))
+        )));
+    }
+
+    #[test]
+    fn assignment_associates_to_the_right() {
+        assert_eq!(parse("a = b = 1"), Ok(Expression::Assign(
+            identifier("a"),
+            Box::new(Expression::Assign(identifier("b"), number(1.0)))
+        )));
+    }
+
+    #[test]
+    fn member_accesses() {
+        assert_eq!(parse("a.b['c'][0]"), Ok(Expression::Member(
+            Box::new(Expression::Member(
+                Box::new(Expression::Member(identifier("a"), Key::String("b".to_owned()))),
+                Key::String("c".to_owned())
+            )),
+            Key::Index(0)
+        )));
+    }
+
+    #[test]
+    fn calls() {
+        assert_eq!(parse("f()"), Ok(Expression::Call(identifier("f"), Vec::new())));
+        assert_eq!(parse("o.m(1, x = 2)(this)"), Ok(Expression::Call(
+            Box::new(Expression::Call(
+                Box::new(Expression::Member(identifier("o"), Key::String("m".to_owned()))),
+                vec![*number(1.0), Expression::Assign(identifier("x"), number(2.0))]
+            )),
+            vec![Expression::This]
+        )));
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(parse("undefined"), Ok(Expression::Literal(Slot::Undefined)));
+        assert_eq!(parse("null"), Ok(Expression::Literal(Slot::Null)));
+        assert_eq!(parse("true"), Ok(Expression::Literal(Slot::Boolean(true))));
+        assert_eq!(parse("'a b'"), Ok(Expression::Literal(Slot::String("a b".to_owned()))));
+        let bigint = BigInt::new(false, vec![0, 1]);
+        assert_eq!(parse("4294967296n"), Ok(Expression::Literal(Slot::BigInt(bigint))));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse("").is_err());
+        assert!(parse("1 +").is_err());
+        assert!(parse("(a").is_err());
+        assert!(parse("a b").is_err());
+        assert!(parse("f(a b)").is_err());
+        assert!(parse("a.1").is_err());
+        assert!(parse("a[b]").is_err());
+        assert!(parse("1 = a").is_err());
+        assert!(parse("'abc").is_err());
+        assert!(parse("a # b").is_err());
+        assert!(parse("1.2.3").is_err());
+    }
+}
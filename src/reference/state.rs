@@ -0,0 +1,518 @@
+//! The state of a simulated debuggee.
+//!
+//! Entities refer to each other by their index in the vector that holds them.
+//! Entities are never removed, so an index stays valid for as long as the state
+//! lives.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+use {AllocationLogEntry, BigInt, BreakpointHandler, DebuggerStatementHandler, ElementType,
+     EnterFrameHandler, EnvironmentType, ExceptionUnwindHandler, FrameType, FunctionKind,
+     IntroductionType, NewScriptHandler, PopHandler, PromiseState, PropertyKey, SavedFrame,
+     StepHandler};
+
+use super::Reference;
+use super::parser::Expression;
+
+/// A value as stored in the state. This mirrors `Value`, except that objects
+/// and symbols are referred to by index.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Slot {
+    Undefined,
+    Null,
+    Boolean(bool),
+    String(String),
+    Number(f64),
+    BigInt(BigInt),
+    Symbol(usize),
+    Object(usize)
+}
+
+/// A property key as stored in the state. This mirrors `PropertyKey`, except
+/// that symbols are referred to by index.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Key {
+    String(String),
+    Symbol(usize),
+    Index(u32)
+}
+
+impl Key {
+    /// Returns the key for the property with the given `name`.
+    pub fn from_name(name: &str) -> Key {
+        match PropertyKey::<Reference>::parse_index(name) {
+            Some(index) => Key::Index(index),
+            None => Key::String(name.to_owned())
+        }
+    }
+
+    /// Returns the name of the property with the key. If the key is a symbol,
+    /// returns `None` instead.
+    pub fn name(&self) -> Option<String> {
+        match *self {
+            Key::String(ref name) => Some(name.clone()),
+            Key::Symbol(_) => None,
+            Key::Index(index) => Some(index.to_string())
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Key::String(ref name) => formatter.write_str(name),
+            Key::Symbol(_) => formatter.write_str("Symbol()"),
+            Key::Index(index) => write!(formatter, "{}", index)
+        }
+    }
+}
+
+pub type BreakpointHandlerRef = Rc<Box<dyn BreakpointHandler<Reference>>>;
+
+pub struct BreakpointData {
+    pub id: u64,
+    pub enabled: bool,
+    pub handler: BreakpointHandlerRef
+}
+
+pub struct DebuggerData {
+    pub debuggees: Vec<usize>,
+    pub enabled: bool,
+    pub recording_allocations: bool,
+    pub allocations_log: Vec<AllocationLogEntry>,
+    pub debugger_statement_handler: Option<Rc<Box<dyn DebuggerStatementHandler<Reference>>>>,
+    pub enter_frame_handler: Option<Rc<Box<dyn EnterFrameHandler<Reference>>>>,
+    pub exception_unwind_handler: Option<Rc<Box<dyn ExceptionUnwindHandler<Reference>>>>,
+    pub new_script_handler: Option<Rc<Box<dyn NewScriptHandler<Reference>>>>
+}
+
+pub struct EnvironmentData {
+    pub kind: EnvironmentType,
+    pub global: usize,
+    pub parent: Option<usize>,
+    pub bindings: Vec<(String, Slot)>,
+    pub object: Option<usize>,
+    pub callee: Option<usize>,
+    pub optimized_out: bool
+}
+
+pub struct FrameData {
+    pub kind: FrameType,
+    pub global: usize,
+    pub script: usize,
+    pub offset: u32,
+    pub depth: u32,
+    pub environment: usize,
+    pub variables: usize,
+    pub callee: Option<usize>,
+    pub this: Slot,
+    pub arguments: Vec<Slot>,
+    pub constructing: bool,
+    pub live: bool,
+    pub suspended: bool,
+    /// The promise returned by the call of an async function, if the frame
+    /// is the frame of that call.
+    pub promise: Option<usize>,
+    pub older: Option<usize>,
+    pub pop_handler: Option<Rc<Box<dyn PopHandler<Reference>>>>,
+    pub step_handler: Option<Rc<Box<dyn StepHandler<Reference>>>>
+}
+
+#[derive(Clone)]
+pub struct BoundFunction {
+    pub target: usize,
+    pub this: Slot,
+    pub arguments: Vec<Slot>
+}
+
+#[derive(Clone)]
+pub struct FunctionData {
+    pub name: Option<String>,
+    pub display_name: Option<String>,
+    pub parameters: Vec<String>,
+    pub script: Option<usize>,
+    pub environment: Option<usize>,
+    pub kind: FunctionKind,
+    pub bound: Option<BoundFunction>
+}
+
+#[derive(Clone, PartialEq)]
+pub enum PropertyKind {
+    Data { value: Slot, writable: bool },
+    Accessor { get: Slot, set: Slot }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Property {
+    pub configurable: bool,
+    pub enumerable: bool,
+    pub kind: PropertyKind
+}
+
+pub struct BufferData {
+    pub shared: bool,
+    /// The bytes held by the buffer, or `None` if it is detached.
+    pub bytes: Option<Vec<u8>>
+}
+
+pub struct ViewData {
+    pub buffer: usize,
+    pub offset: usize,
+    pub length: usize,
+    pub element_type: Option<ElementType>
+}
+
+pub struct ErrorData {
+    pub name: String,
+    pub message: String,
+    pub stack: Vec<SavedFrame>
+}
+
+pub enum CollectionData {
+    Map(Vec<(Slot, Slot)>),
+    Set(Vec<Slot>),
+    WeakMap(Vec<(usize, Slot)>),
+    WeakSet(Vec<usize>)
+}
+
+impl CollectionData {
+    pub fn size(&self) -> usize {
+        match *self {
+            CollectionData::Map(ref entries) => entries.len(),
+            CollectionData::Set(ref values) => values.len(),
+            CollectionData::WeakMap(ref entries) => entries.len(),
+            CollectionData::WeakSet(ref keys) => keys.len()
+        }
+    }
+}
+
+pub struct ProxyData {
+    pub target: usize,
+    pub handler: usize,
+    pub revoked: bool
+}
+
+pub struct PromiseData {
+    pub state: PromiseState,
+    pub result: Slot,
+    pub allocation_site: Option<Vec<SavedFrame>>,
+    pub resolution_site: Option<Vec<SavedFrame>>,
+    pub dependents: Vec<usize>
+}
+
+/// The number of bytes occupied by an object, not counting its properties.
+const OBJECT_SIZE: usize = 32;
+
+/// The number of additional bytes occupied by an object for each of its
+/// properties.
+const PROPERTY_SIZE: usize = 16;
+
+/// The number of additional bytes occupied by a collection for each of its
+/// entries.
+const ENTRY_SIZE: usize = 16;
+
+/// The number of additional bytes occupied by a function.
+const FUNCTION_SIZE: usize = 32;
+
+pub struct ObjectData {
+    pub class: String,
+    pub global: usize,
+    pub prototype: Option<usize>,
+    pub extensible: bool,
+    pub properties: Vec<(Key, Property)>,
+    pub private_fields: Vec<(String, Slot)>,
+    pub private_methods: Vec<(String, Property)>,
+    pub function: Option<FunctionData>,
+    pub environment: Option<usize>,
+    pub buffer: Option<BufferData>,
+    pub view: Option<ViewData>,
+    pub collection: Option<CollectionData>,
+    pub error: Option<ErrorData>,
+    pub promise: Option<PromiseData>,
+    pub proxy: Option<ProxyData>,
+    /// The frame of a generator object, or of the call of an async function
+    /// that returned the promise.
+    pub frame: Option<usize>,
+    pub allocation_site: Option<Vec<SavedFrame>>
+}
+
+impl ObjectData {
+    pub fn new(class: &str, global: usize) -> ObjectData {
+        ObjectData {
+            class: class.to_owned(),
+            global,
+            prototype: None,
+            extensible: true,
+            properties: Vec::new(),
+            private_fields: Vec::new(),
+            private_methods: Vec::new(),
+            function: None,
+            environment: None,
+            buffer: None,
+            view: None,
+            collection: None,
+            error: None,
+            promise: None,
+            proxy: None,
+            frame: None,
+            allocation_site: None
+        }
+    }
+
+    /// Returns the number of bytes occupied by the object, as counted by a
+    /// census.
+    pub fn size(&self) -> usize {
+        let function_size = if self.function.is_some() { FUNCTION_SIZE } else { 0 };
+        let entries = self.collection.as_ref().map_or(0, CollectionData::size);
+        let bytes = self.buffer.as_ref().and_then(|buffer| buffer.bytes.as_ref()).map_or(0, Vec::len);
+        let properties = self.properties.len() + self.private_fields.len() + self.private_methods.len();
+        OBJECT_SIZE + PROPERTY_SIZE * properties + ENTRY_SIZE * entries + bytes + function_size
+    }
+
+    pub fn property(&self, key: &Key) -> Option<&Property> {
+        self.properties.iter().find(|&(other, _)| other == key).map(|(_, property)| property)
+    }
+
+    pub fn property_mut(&mut self, key: &Key) -> Option<&mut Property> {
+        self.properties.iter_mut().find(|&&mut (ref other, _)| other == key).map(|&mut (_, ref mut property)| property)
+    }
+
+    /// Returns the keys of the own properties of the object, in the order
+    /// required by JavaScript: index keys in ascending order, followed by
+    /// string keys and symbol keys, each in the order in which they were
+    /// added.
+    pub fn own_keys(&self) -> Vec<Key> {
+        let mut indices: Vec<u32> = self.properties.iter().filter_map(|(key, _)| match *key {
+            Key::Index(index) => Some(index),
+            _ => None
+        }).collect();
+        indices.sort();
+        let keys = self.properties.iter().map(|(key, _)| key);
+        let strings = keys.clone().filter(|key| matches!(**key, Key::String(_)));
+        let symbols = keys.filter(|key| matches!(**key, Key::Symbol(_)));
+        indices.into_iter().map(Key::Index).chain(strings.cloned()).chain(symbols.cloned()).collect()
+    }
+}
+
+/// An operation of a script, as executed by the interpreter.
+#[derive(Clone)]
+pub enum Operation {
+    Debugger,
+    Expression(Expression),
+    Var(String, Expression),
+    Return(Expression),
+    Throw(Expression),
+    Yield(Expression),
+    Await(Expression),
+    EnterWith(Expression),
+    LeaveWith,
+    Function(usize)
+}
+
+#[derive(Clone)]
+pub struct Instruction {
+    pub line: u32,
+    pub column: u32,
+    pub operation: Operation
+}
+
+pub struct ScriptData {
+    pub global: usize,
+    pub source: usize,
+    pub source_start: u32,
+    pub source_length: u32,
+    pub start_line: u32,
+    pub display_name: Option<String>,
+    pub name: Option<String>,
+    pub parameters: Vec<String>,
+    pub kind: FunctionKind,
+    pub children: Vec<usize>,
+    pub instructions: Vec<Instruction>,
+    pub breakpoints: BTreeMap<u32, Vec<BreakpointData>>
+}
+
+impl ScriptData {
+    /// Returns the number of lines spanned by the code of the script.
+    pub fn line_count(&self) -> u32 {
+        let end_line = self.instructions.iter().map(|instruction| instruction.line).max();
+        end_line.map_or(0, |end_line| end_line + 1 - self.start_line)
+    }
+
+    /// Returns `true` if the code of the script spans the given `line`.
+    pub fn spans(&self, line: u32) -> bool {
+        self.start_line <= line && line < self.start_line + self.line_count()
+    }
+
+    /// Returns `true` if the instruction at the given `offset` is the first
+    /// instruction for its line.
+    pub fn is_entry_point(&self, offset: usize) -> bool {
+        offset == 0 || self.instructions[offset - 1].line != self.instructions[offset].line
+    }
+}
+
+pub struct SourceData {
+    pub url: String,
+    pub text: String,
+    pub source_map_url: Option<String>,
+    pub element: Option<usize>,
+    pub element_attribute_name: Option<String>,
+    pub introduction_type: Option<IntroductionType>,
+    pub introduction_script: Option<usize>,
+    pub introduction_offset: Option<u32>
+}
+
+pub struct SymbolData {
+    pub description: Option<String>,
+    pub registry_key: Option<String>
+}
+
+#[derive(Default)]
+pub struct State {
+    pub debuggers: Vec<DebuggerData>,
+    pub environments: Vec<EnvironmentData>,
+    pub frames: Vec<FrameData>,
+    pub objects: Vec<ObjectData>,
+    pub scripts: Vec<ScriptData>,
+    pub sources: Vec<SourceData>,
+    pub symbols: Vec<SymbolData>,
+    pub stack: Vec<usize>,
+    /// The number of objects allocated while allocations were recorded. This
+    /// serves as the timestamp of allocation log entries.
+    pub allocations: u64,
+    /// The number of breakpoints set so far. This serves as the identifier of
+    /// the next breakpoint.
+    pub breakpoints: u64
+}
+
+/// Returns the given `stack` as a site, or `None` if it is empty.
+pub fn site(stack: Vec<SavedFrame>) -> Option<Vec<SavedFrame>> {
+    if stack.is_empty() {
+        None
+    } else {
+        Some(stack)
+    }
+}
+
+impl State {
+    /// Returns `true` if the given `global` is a debuggee of any debugger.
+    pub fn is_debuggee(&self, global: usize) -> bool {
+        self.debuggers.iter().any(|debugger| debugger.debuggees.contains(&global))
+    }
+
+    /// Returns `true` if the given `global` is a debuggee of any enabled
+    /// debugger. Handlers are only called for code running in such a global.
+    pub fn is_observed(&self, global: usize) -> bool {
+        self.debuggers.iter().any(|debugger| {
+            debugger.enabled && debugger.debuggees.contains(&global)
+        })
+    }
+
+    /// Returns the handlers, as selected by the given `select` function, of the
+    /// enabled debuggers that have the given `global` as a debuggee.
+    pub fn handlers<T: Clone>(&self, global: usize, select: fn(&DebuggerData) -> &Option<T>) -> Vec<T> {
+        self.debuggers.iter().filter(|debugger| {
+            debugger.enabled && debugger.debuggees.contains(&global)
+        }).filter_map(|debugger| select(debugger).clone()).collect()
+    }
+
+    /// Adds the given `object` to the state. If a debugger is recording
+    /// allocations in the global of the object, saves the stack as its
+    /// allocation site, and logs the allocation.
+    pub fn add_object(&mut self, mut object: ObjectData) -> usize {
+        let recorders: Vec<usize> = (0..self.debuggers.len()).filter(|&index| {
+            let debugger = &self.debuggers[index];
+            debugger.enabled && debugger.recording_allocations &&
+            debugger.debuggees.contains(&object.global)
+        }).collect();
+        if !recorders.is_empty() {
+            let stack = self.save_stack();
+            self.allocations += 1;
+            for index in recorders {
+                self.debuggers[index].allocations_log.push(AllocationLogEntry {
+                    timestamp: self.allocations,
+                    class: object.class.clone(),
+                    size: object.size(),
+                    stack: stack.clone()
+                });
+            }
+            object.allocation_site = site(stack);
+        }
+        self.objects.push(object);
+        self.objects.len() - 1
+    }
+
+    /// If the given `object` is an array buffer or a view on one, returns the
+    /// index of the buffer, along with the range of bytes in the buffer that
+    /// the object covers. The range is empty if the buffer is detached.
+    pub fn binary(&self, object: usize) -> Option<(usize, Range<usize>)> {
+        let data = &self.objects[object];
+        let (buffer, range) = match (&data.buffer, &data.view) {
+            (Some(buffer), _) => (object, 0..buffer.bytes.as_ref().map_or(0, Vec::len)),
+            (None, Some(view)) => (view.buffer, view.offset..view.offset + view.length),
+            (None, None) => return None
+        };
+        let detached = self.objects[buffer].buffer.as_ref().map_or(false, |buffer| buffer.bytes.is_none());
+        Some((buffer, if detached { 0..0 } else { range }))
+    }
+
+    /// Adds a new pending promise in the given `global`, and returns its
+    /// index.
+    pub fn add_promise(&mut self, global: usize) -> usize {
+        let mut object = ObjectData::new("Promise", global);
+        object.promise = Some(PromiseData {
+            state: PromiseState::Pending,
+            result: Slot::Undefined,
+            allocation_site: site(self.save_stack()),
+            resolution_site: None,
+            dependents: Vec::new()
+        });
+        self.add_object(object)
+    }
+
+    /// Settles the given `promise` in the given `state` with the given
+    /// `result`, along with the promises that depend on it. Does nothing if
+    /// the promise is already settled.
+    pub fn settle_promise(&mut self, promise: usize, state: PromiseState, result: Slot) {
+        let resolution_site = site(self.save_stack());
+        let mut promises = vec![promise];
+        while let Some(promise) = promises.pop() {
+            if let Some(ref mut data) = self.objects[promise].promise {
+                if data.state == PromiseState::Pending {
+                    data.state = state;
+                    data.result = result.clone();
+                    data.resolution_site = resolution_site.clone();
+                    promises.extend(data.dependents.iter().cloned());
+                }
+            }
+        }
+    }
+
+    /// Returns the frames on the stack as saved frames, youngest first.
+    pub fn save_stack(&self) -> Vec<SavedFrame> {
+        self.stack.iter().rev().map(|&frame| {
+            let frame = &self.frames[frame];
+            let script = &self.scripts[frame.script];
+            let (line, column) = script.instructions.get(frame.offset as usize).map_or(
+                (script.start_line, 0),
+                |instruction| (instruction.line, instruction.column)
+            );
+            let function_name = frame.callee.and_then(|callee| {
+                self.objects[callee].function.as_ref().and_then(|function| function.name.clone())
+            });
+            SavedFrame {
+                url: self.sources[script.source].url.clone(),
+                line,
+                column,
+                function_name
+            }
+        }).collect()
+    }
+
+    pub fn add_environment(&mut self, environment: EnvironmentData) -> usize {
+        self.environments.push(environment);
+        self.environments.len() - 1
+    }
+}
@@ -0,0 +1,745 @@
+//! Stepping through the code of a debuggee.
+//!
+//! A step is built on the step and pop handlers of the frames it involves, and,
+//! when stepping in, on the enter frame handler of the debugger. While a step
+//! is in progress, it replaces these handlers, and calls the handlers it
+//! replaced after its own. When it finishes, it restores the handlers it
+//! replaced, unless they were replaced again in the meantime.
+//!
+//! A step only pauses at the entry points of lines, as given by
+//! `Script::get_all_line_offsets`, and never at an entry point on the line at
+//! which it started. If a frame involved in a step is popped before the step
+//! pauses, whether because it completed or because it was suspended, the step
+//! continues in the next-older frame. If there is no such frame, the step
+//! finishes without pausing.
+//!
+//! Running to a location is built on one-shot breakpoints instead. These are
+//! removed as soon as one of them is hit, before the pause handler is called,
+//! so that code run by the pause handler cannot hit them again.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use {Backend, BreakpointHandler, BreakpointId, CompletionValue, Debugger, EnterFrameHandler, Error,
+     Fallible, Frame, Object, PopHandler, ResumptionValue, Script, StepHandler, descendants};
+
+/// A trait for values that can be used as pause handler.
+pub trait PauseHandler<B: Backend> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B>;
+}
+
+struct State<B: Backend> {
+    handler: Rc<Box<dyn PauseHandler<B>>>,
+    /// The debugger whose enter frame handler the step replaced, if any.
+    debugger: Option<Debugger<B>>,
+    enter_frame_handler: Option<Rc<Box<dyn EnterFrameHandler<B>>>>,
+    /// The enter frame handler that the step installed, if any.
+    enterer: RefCell<Option<Rc<Box<dyn EnterFrameHandler<B>>>>>,
+    /// The function into which the step enters, if the step enters only into
+    /// a specific function.
+    target: Option<Object<B>>,
+    frames: RefCell<Vec<Watched<B>>>,
+    breakpoints: RefCell<Vec<(Script<B>, BreakpointId)>>,
+    active: Cell<bool>
+}
+
+impl<B: Backend> State<B> {
+    fn new(handler: Rc<Box<dyn PauseHandler<B>>>) -> State<B> {
+        State {
+            handler,
+            debugger: None,
+            enter_frame_handler: None,
+            enterer: RefCell::new(None),
+            target: None,
+            frames: RefCell::new(Vec::new()),
+            breakpoints: RefCell::new(Vec::new()),
+            active: Cell::new(true)
+        }
+    }
+}
+
+/// A frame involved in a step, along with the handlers that the step replaced
+/// and those that it installed in their place.
+struct Watched<B: Backend> {
+    frame: Frame<B>,
+    step_handler: Option<Rc<Box<dyn StepHandler<B>>>>,
+    pop_handler: Option<Rc<Box<dyn PopHandler<B>>>>,
+    stepper: Option<Rc<Box<dyn StepHandler<B>>>>,
+    popper: Option<Rc<Box<dyn PopHandler<B>>>>
+}
+
+/// A step in progress.
+pub struct Step<B: Backend> {
+    state: Rc<State<B>>
+}
+
+impl<B: Backend> Step<B> {
+    /// Cancels the step, without pausing. Does nothing if the step already
+    /// finished.
+    pub fn cancel(&self) {
+        finish(&self.state);
+    }
+
+    /// Returns `true` if the step is still in progress. Returns `false`
+    /// otherwise.
+    pub fn is_active(&self) -> bool {
+        self.state.active.get()
+    }
+}
+
+/// Steps into the next line, starting from the given `frame`. This pauses at
+/// the next line entry point in the frame, or at the first line entry point in
+/// any new frame that is pushed on the stack before that, including the frames
+/// of resumed generators and async functions. When the step pauses, the
+/// `handle` method of the given `handler` is called with the frame at which it
+/// paused.
+pub fn step_in<B: Backend>(debugger: &Debugger<B>, frame: &Frame<B>,
+                           handler: Rc<Box<dyn PauseHandler<B>>>) -> Step<B> {
+    let mut state = State::new(handler);
+    state.debugger = Some(debugger.clone());
+    state.enter_frame_handler = debugger.enter_frame_handler();
+    let state = Rc::new(state);
+    watch(&state, frame, true);
+    enter(&state, debugger);
+    Step { state }
+}
+
+/// Steps into the given `target` function, starting from the given `frame`.
+/// This is like `step_in`, except that frames that are pushed on the stack
+/// for any other function do not cause the step to pause. If the target is a
+/// bound function, the step pauses in its target function instead. This is
+/// intended for lines with several calls, where the user picks which of the
+/// functions to step into.
+pub fn step_into_target<B: Backend>(debugger: &Debugger<B>, frame: &Frame<B>, target: &Object<B>,
+                                    handler: Rc<Box<dyn PauseHandler<B>>>) -> Step<B> {
+    let mut target = target.clone();
+    while let Some(bound_target) = target.bound_target_function() {
+        target = bound_target;
+    }
+    let mut state = State::new(handler);
+    state.debugger = Some(debugger.clone());
+    state.enter_frame_handler = debugger.enter_frame_handler();
+    state.target = Some(target);
+    let state = Rc::new(state);
+    watch(&state, frame, true);
+    enter(&state, debugger);
+    Step { state }
+}
+
+/// Steps over the next line, starting from the given `frame`. This pauses at
+/// the next line entry point in the frame. Frames called by the frame do not
+/// cause the step to pause. When the step pauses, the `handle` method of the
+/// given `handler` is called with the frame at which it paused.
+pub fn step_over<B: Backend>(_debugger: &Debugger<B>, frame: &Frame<B>,
+                             handler: Rc<Box<dyn PauseHandler<B>>>) -> Step<B> {
+    let state = Rc::new(State::new(handler));
+    watch(&state, frame, true);
+    Step { state }
+}
+
+/// Steps out of the given `frame`. This pauses at the next line entry point in
+/// the next-older frame, once the frame is popped. When the step pauses, the
+/// `handle` method of the given `handler` is called with the frame at which it
+/// paused.
+pub fn step_out<B: Backend>(_debugger: &Debugger<B>, frame: &Frame<B>,
+                            handler: Rc<Box<dyn PauseHandler<B>>>) -> Step<B> {
+    let state = Rc::new(State::new(handler));
+    install(&state, frame, None);
+    Step { state }
+}
+
+/// Runs until the given `offset` in the given `script` is reached. When it is,
+/// the `handle` method of the given `handler` is called with the frame that
+/// reached it.
+///
+/// # Errors
+/// If the given `offset` is not a valid offset in the given script, returns
+/// `OffsetNotValid`.
+pub fn run_to_offset<B: Backend>(script: &Script<B>, offset: u32,
+                                 handler: Rc<Box<dyn PauseHandler<B>>>) -> Fallible<Step<B>> {
+    let state = Rc::new(State::new(handler));
+    set_breakpoints(&state, &[(script.clone(), offset)])?;
+    Ok(Step { state })
+}
+
+/// Runs until the given `line` in the given `script` is reached; that is,
+/// until one of the line entry points for the line is reached, in the script
+/// or in any of its descendants. When it is, the `handle` method of the given
+/// `handler` is called with the frame that reached it.
+///
+/// # Errors
+/// If the given line has no line entry points in the given script or in any
+/// of its descendants, returns `LineNotValid`.
+pub fn run_to_line<B: Backend>(script: &Script<B>, line: u32,
+                               handler: Rc<Box<dyn PauseHandler<B>>>) -> Fallible<Step<B>> {
+    let locations: Vec<(Script<B>, u32)> = descendants(script).into_iter().flat_map(|script| {
+        script.get_line_offsets(line).into_iter().map(move |offset| (script.clone(), offset))
+    }).collect();
+    if locations.is_empty() {
+        return Err(Error::LineNotValid);
+    }
+    let state = Rc::new(State::new(handler));
+    set_breakpoints(&state, &locations)?;
+    Ok(Step { state })
+}
+
+/// Sets a one-shot breakpoint at each of the given `locations`, given as a
+/// script and an offset in that script, for the given step.
+fn set_breakpoints<B: Backend>(state: &Rc<State<B>>, locations: &[(Script<B>, u32)]) -> Fallible<()> {
+    let handler: Rc<Box<dyn BreakpointHandler<B>>> = Rc::new(Box::new(Breaker { state: state.clone() }));
+    for &(ref script, offset) in locations {
+        match script.set_breakpoint(offset, handler.clone()) {
+            Ok(id) => state.breakpoints.borrow_mut().push((script.clone(), id)),
+            Err(error) => {
+                finish(state);
+                return Err(error);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Makes the given `frame` pause at the next line entry point, and continue in
+/// the next-older frame when popped. If `skip_line` is `true`, line entry
+/// points on the line at which the frame is executing are skipped.
+fn watch<B: Backend>(state: &Rc<State<B>>, frame: &Frame<B>, skip_line: bool) {
+    let entry_points = frame.script().map(|script| entry_points(&script)).unwrap_or_default();
+    let start_line = match frame.offset() {
+        Some(offset) if skip_line => entry_points.range(..=offset).next_back().map(|(_, &line)| line),
+        _ => None
+    };
+    install(state, frame, Some((entry_points, start_line)));
+}
+
+/// Installs a pop handler for the given step on the given `frame` and, if
+/// given the line entry points of the frame and the line to skip, a step
+/// handler as well. A frame that is watched again keeps the handlers that it
+/// had before it was first watched.
+fn install<B: Backend>(state: &Rc<State<B>>, frame: &Frame<B>,
+                       stepper: Option<(BTreeMap<u32, u32>, Option<u32>)>) {
+    let mut frames = state.frames.borrow_mut();
+    let index = match frames.iter().position(|watched| watched.frame == *frame) {
+        Some(index) => index,
+        None => {
+            frames.push(Watched {
+                frame: frame.clone(),
+                step_handler: frame.step_handler(),
+                pop_handler: frame.pop_handler(),
+                stepper: None,
+                popper: None
+            });
+            frames.len() - 1
+        }
+    };
+    let watched = &mut frames[index];
+    if let Some((entry_points, start_line)) = stepper {
+        let stepper: Rc<Box<dyn StepHandler<B>>> = Rc::new(Box::new(Stepper {
+            state: state.clone(),
+            handler: watched.step_handler.clone(),
+            entry_points,
+            start_line
+        }));
+        frame.set_step_handler(Some(stepper.clone()));
+        watched.stepper = Some(stepper);
+    }
+    let popper: Rc<Box<dyn PopHandler<B>>> = Rc::new(Box::new(Popper {
+        state: state.clone(),
+        handler: watched.pop_handler.clone()
+    }));
+    frame.set_pop_handler(Some(popper.clone()));
+    watched.popper = Some(popper);
+}
+
+/// Installs an enter frame handler for the given step on the given `debugger`.
+fn enter<B: Backend>(state: &Rc<State<B>>, debugger: &Debugger<B>) {
+    let enterer: Rc<Box<dyn EnterFrameHandler<B>>> = Rc::new(Box::new(Enterer { state: state.clone() }));
+    debugger.set_enter_frame_handler(Some(enterer.clone()));
+    *state.enterer.borrow_mut() = Some(enterer);
+}
+
+/// Finishes the given step, and then calls its pause handler with the given
+/// `frame`.
+fn pause<B: Backend>(state: &Rc<State<B>>, frame: &Frame<B>) -> ResumptionValue<B> {
+    finish(state);
+    state.handler.handle(frame)
+}
+
+/// Finishes the given step by restoring the handlers it replaced, unless they
+/// were replaced again since. Does nothing if the step already finished.
+fn finish<B: Backend>(state: &Rc<State<B>>) {
+    if !state.active.replace(false) {
+        return;
+    }
+    let frames: Vec<Watched<B>> = state.frames.borrow_mut().drain(..).collect();
+    for watched in frames {
+        if is_installed(watched.frame.step_handler(), &watched.stepper) {
+            watched.frame.set_step_handler(watched.step_handler);
+        }
+        if is_installed(watched.frame.pop_handler(), &watched.popper) {
+            watched.frame.set_pop_handler(watched.pop_handler);
+        }
+    }
+    let enterer = state.enterer.borrow_mut().take();
+    if let Some(ref debugger) = state.debugger {
+        if is_installed(debugger.enter_frame_handler(), &enterer) {
+            debugger.set_enter_frame_handler(state.enter_frame_handler.clone());
+        }
+    }
+    let breakpoints: Vec<(Script<B>, BreakpointId)> = state.breakpoints.borrow_mut().drain(..).collect();
+    for (script, id) in breakpoints {
+        // The breakpoint may have been cleared by someone else in the meantime.
+        let _ = script.remove_breakpoint(id);
+    }
+}
+
+/// Returns the line of each line entry point in the given `script`, by offset.
+fn entry_points<B: Backend>(script: &Script<B>) -> BTreeMap<u32, u32> {
+    script.get_all_line_offsets().into_iter().flat_map(|(line, offsets)| {
+        offsets.into_iter().map(move |offset| (offset, line))
+    }).collect()
+}
+
+/// Returns `true` if the given `handler` installed by a step is the `current`
+/// handler. Returns `false` otherwise.
+fn is_installed<T: ?Sized>(current: Option<Rc<Box<T>>>, handler: &Option<Rc<Box<T>>>) -> bool {
+    match (current, handler) {
+        (Some(ref current), Some(ref handler)) => Rc::ptr_eq(current, handler),
+        _ => false
+    }
+}
+
+struct Stepper<B: Backend> {
+    state: Rc<State<B>>,
+    /// The step handler that this handler replaced, if any.
+    handler: Option<Rc<Box<dyn StepHandler<B>>>>,
+    /// The line of each line entry point in the script of the frame, by
+    /// offset.
+    entry_points: BTreeMap<u32, u32>,
+    start_line: Option<u32>
+}
+
+impl<B: Backend> StepHandler<B> for Stepper<B> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B> {
+        let line = match frame.offset() {
+            Some(offset) if self.state.active.get() => self.entry_points.get(&offset).cloned(),
+            _ => None
+        };
+        let resumption = match line {
+            Some(line) if Some(line) != self.start_line => pause(&self.state, frame),
+            _ => None
+        };
+        resumption.or_else(|| self.handler.as_ref().and_then(|handler| handler.handle(frame)))
+    }
+}
+
+struct Popper<B: Backend> {
+    state: Rc<State<B>>,
+    /// The pop handler that this handler replaced, if any.
+    handler: Option<Rc<Box<dyn PopHandler<B>>>>
+}
+
+impl<B: Backend> PopHandler<B> for Popper<B> {
+    fn handle(&self, frame: &Frame<B>, completion: &CompletionValue<B>) -> ResumptionValue<B> {
+        if self.state.active.get() {
+            match frame.older() {
+                Some(older) => watch(&self.state, &older, true),
+                None => finish(&self.state)
+            }
+        }
+        self.handler.as_ref().and_then(|handler| handler.handle(frame, completion))
+    }
+}
+
+struct Enterer<B: Backend> {
+    state: Rc<State<B>>
+}
+
+impl<B: Backend> EnterFrameHandler<B> for Enterer<B> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B> {
+        let entered = match self.state.target {
+            Some(ref target) => frame.callee().as_ref() == Some(target),
+            None => true
+        };
+        if self.state.active.get() && entered {
+            watch(&self.state, frame, false);
+        }
+        self.state.enter_frame_handler.as_ref().and_then(|handler| handler.handle(frame))
+    }
+}
+
+struct Breaker<B: Backend> {
+    state: Rc<State<B>>
+}
+
+impl<B: Backend> BreakpointHandler<B> for Breaker<B> {
+    fn handle(&self, frame: &Frame<B>) -> ResumptionValue<B> {
+        if self.state.active.get() {
+            pause(&self.state, frame)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use reference::{Op, Reference, Runtime, ScriptBuilder, SourceBuilder};
+    use {CompletionValue, Debugger, DebuggerStatementHandler, EnterFrameHandler, Error, Frame, FunctionKind,
+         Object, PopHandler, ResumptionValue, StepHandler, Value};
+
+    use super::{run_to_line, step_in, step_into_target, step_out, step_over, PauseHandler};
+
+    /// A handler that records each call in a shared log.
+    #[derive(Clone)]
+    struct Recorder {
+        log: Rc<RefCell<Vec<String>>>
+    }
+
+    impl Recorder {
+        fn new() -> Recorder {
+            Recorder { log: Rc::new(RefCell::new(Vec::new())) }
+        }
+
+        fn record(&self, entry: String) -> ResumptionValue<Reference> {
+            self.log.borrow_mut().push(entry);
+            None
+        }
+
+        fn log(&self) -> Vec<String> {
+            self.log.borrow().clone()
+        }
+    }
+
+    fn describe(frame: &Frame<Reference>) -> String {
+        let name = frame.callee().and_then(|callee| callee.name()).unwrap_or_else(|| "global".to_owned());
+        format!("{}@{}", name, frame.offset().unwrap())
+    }
+
+    impl EnterFrameHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("enter {}", describe(frame)))
+        }
+    }
+
+    impl PauseHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("pause {}", describe(frame)))
+        }
+    }
+
+    impl PopHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>, _completion: &CompletionValue<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("pop {}", describe(frame)))
+        }
+    }
+
+    impl StepHandler<Reference> for Recorder {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.record(format!("step {}", describe(frame)))
+        }
+    }
+
+    /// A debugger statement handler that calls a function with the frame in
+    /// which it is called.
+    struct Starter<F: Fn(&Frame<Reference>)>(F);
+
+    impl<F: Fn(&Frame<Reference>)> DebuggerStatementHandler<Reference> for Starter<F> {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            (self.0)(frame);
+            None
+        }
+    }
+
+    /// A pause handler that records each pause in a shared log, and then calls
+    /// a function with the frame at which the step paused.
+    struct Pauser<F: Fn(&Frame<Reference>)>(Recorder, F);
+
+    impl<F: Fn(&Frame<Reference>)> PauseHandler<Reference> for Pauser<F> {
+        fn handle(&self, frame: &Frame<Reference>) -> ResumptionValue<Reference> {
+            self.0.record(format!("pause {}", describe(frame)));
+            (self.1)(frame);
+            None
+        }
+    }
+
+    fn setup() -> (Runtime, Object<Reference>, Debugger<Reference>) {
+        let runtime = Runtime::new();
+        let global = runtime.new_global();
+        let debugger = runtime.new_debugger();
+        debugger.add_debuggee(&global);
+        (runtime, global, debugger)
+    }
+
+    fn execute(runtime: &Runtime, global: &Object<Reference>, builder: ScriptBuilder) -> CompletionValue<Reference> {
+        let source = runtime.new_source(SourceBuilder::new("test.js", ""));
+        let script = runtime.new_script(global, &source, builder);
+        runtime.execute(&script)
+    }
+
+    #[test]
+    fn step_over_calls_and_restores_the_handlers_it_replaced() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let (handler, stepping) = (recorder.clone(), debugger.clone());
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Starter(move |frame: &Frame<Reference>| {
+            frame.set_step_handler(Some(Rc::new(Box::new(handler.clone()))));
+            frame.set_pop_handler(Some(Rc::new(Box::new(handler.clone()))));
+            step_over(&stepping, frame, Rc::new(Box::new(handler.clone())));
+        })))));
+        let completion = execute(&runtime, &global, ScriptBuilder::new(1)
+            .op(1, Op::Debugger)
+            .op(2, Op::Var("x".to_owned(), "1".to_owned()))
+            .op(3, Op::Return("x".to_owned())));
+        assert_eq!(completion, CompletionValue::Return(Value::Number(1.0)));
+        assert_eq!(recorder.log(), vec!["pause global@1", "step global@1", "step global@2", "pop global@2"]);
+    }
+
+    /// Returns a script that defines a function `f`, and then calls it twice,
+    /// the first time on the same line as a debugger statement.
+    fn call_script() -> ScriptBuilder {
+        ScriptBuilder::new(1)
+            .function(1, "f", &["a"], ScriptBuilder::new(2)
+                .op(2, Op::Return("a".to_owned())))
+            .op(4, Op::Debugger)
+            .op(4, Op::Expression("f(1)".to_owned()))
+            .op(5, Op::Expression("f(2)".to_owned()))
+    }
+
+    #[test]
+    fn step_in_calls_and_restores_the_enter_frame_handler_it_replaced() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        debugger.set_enter_frame_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        let (handler, stepping) = (recorder.clone(), debugger.clone());
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Starter(move |frame: &Frame<Reference>| {
+            step_in(&stepping, frame, Rc::new(Box::new(handler.clone())));
+        })))));
+        execute(&runtime, &global, call_script());
+        assert_eq!(recorder.log(), vec!["enter global@0", "enter f@0", "pause f@0", "enter f@0"]);
+    }
+
+    #[test]
+    fn step_in_keeps_an_enter_frame_handler_installed_in_the_meantime() {
+        let (runtime, global, debugger) = setup();
+        let (recorder, other) = (Recorder::new(), Recorder::new());
+        let (handler, stepping, replacement) = (recorder.clone(), debugger.clone(), other.clone());
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Starter(move |frame: &Frame<Reference>| {
+            let step = step_in(&stepping, frame, Rc::new(Box::new(handler.clone())));
+            stepping.set_enter_frame_handler(Some(Rc::new(Box::new(replacement.clone()))));
+            step.cancel();
+        })))));
+        execute(&runtime, &global, call_script());
+        assert!(recorder.log().is_empty());
+        assert_eq!(other.log(), vec!["enter f@0", "enter f@0"]);
+    }
+
+    #[test]
+    fn step_over_continues_in_the_caller_after_a_return() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let (handler, stepping) = (recorder.clone(), debugger.clone());
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Starter(move |frame: &Frame<Reference>| {
+            step_over(&stepping, frame, Rc::new(Box::new(handler.clone())));
+        })))));
+        let completion = execute(&runtime, &global, ScriptBuilder::new(1)
+            .function(1, "f", &["a"], ScriptBuilder::new(2)
+                .op(2, Op::Debugger)
+                .op(2, Op::Return("a".to_owned())))
+            .op(4, Op::Var("x".to_owned(), "f(1)".to_owned()))
+            .op(4, Op::Var("y".to_owned(), "x".to_owned()))
+            .op(5, Op::Return("y".to_owned())));
+        assert_eq!(completion, CompletionValue::Return(Value::Number(1.0)));
+        assert_eq!(recorder.log(), vec!["pause global@3"]);
+    }
+
+    #[test]
+    fn step_out_pauses_at_the_next_line_of_the_caller() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let (handler, stepping) = (recorder.clone(), debugger.clone());
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Starter(move |frame: &Frame<Reference>| {
+            step_out(&stepping, frame, Rc::new(Box::new(handler.clone())));
+        })))));
+        execute(&runtime, &global, ScriptBuilder::new(1)
+            .function(1, "f", &["a"], ScriptBuilder::new(2)
+                .op(2, Op::Debugger)
+                .op(3, Op::Return("a".to_owned())))
+            .op(5, Op::Var("x".to_owned(), "f(1)".to_owned()))
+            .op(5, Op::Var("y".to_owned(), "x".to_owned()))
+            .op(6, Op::Return("y".to_owned())));
+        assert_eq!(recorder.log(), vec!["pause global@3"]);
+    }
+
+    #[test]
+    fn step_over_does_not_pause_in_the_middle_of_a_line() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let (handler, stepping) = (recorder.clone(), debugger.clone());
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Starter(move |frame: &Frame<Reference>| {
+            frame.set_step_handler(Some(Rc::new(Box::new(handler.clone()))));
+            let (pauser, restepping) = (handler.clone(), stepping.clone());
+            step_over(&stepping, frame, Rc::new(Box::new(Pauser(handler.clone(), move |frame: &Frame<Reference>| {
+                if frame.offset() == Some(1) {
+                    step_over(&restepping, frame, Rc::new(Box::new(pauser.clone())));
+                }
+            }))));
+        })))));
+        execute(&runtime, &global, ScriptBuilder::new(1)
+            .op(1, Op::Debugger)
+            .op(2, Op::Var("x".to_owned(), "1".to_owned()))
+            .op(2, Op::Var("y".to_owned(), "x".to_owned()))
+            .op(2, Op::Var("z".to_owned(), "y".to_owned()))
+            .op(3, Op::Return("z".to_owned())));
+        assert_eq!(recorder.log(), vec![
+            "pause global@1",
+            "step global@1",
+            "step global@2",
+            "step global@3",
+            "pause global@4",
+            "step global@4"
+        ]);
+    }
+
+    #[test]
+    fn step_out_of_a_generator_that_yields_continues_in_the_caller() {
+        let runtime = Rc::new(Runtime::new());
+        let global = runtime.new_global();
+        let debugger = runtime.new_debugger();
+        debugger.add_debuggee(&global);
+        let recorder = Recorder::new();
+        let (handler, stepping, resumer, environment) =
+            (recorder.clone(), debugger.clone(), runtime.clone(), global.as_environment().unwrap());
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Starter(move |frame: &Frame<Reference>| {
+            if frame.callee().is_some() {
+                step_out(&stepping, frame, Rc::new(Box::new(handler.clone())));
+            } else if let Ok(Value::Object(generator)) = environment.get_variable("it") {
+                resumer.resume(&generator);
+            }
+        })))));
+        let source = runtime.new_source(SourceBuilder::new("test.js", ""));
+        let script = runtime.new_script(&global, &source, ScriptBuilder::new(1)
+            .function(1, "g", &[], ScriptBuilder::new(2)
+                .kind(FunctionKind::Generator)
+                .op(2, Op::Debugger)
+                .op(3, Op::Yield("1".to_owned()))
+                .op(4, Op::Return("2".to_owned())))
+            .op(6, Op::Var("it".to_owned(), "g()".to_owned()))
+            .op(7, Op::Debugger)
+            .op(7, Op::Var("x".to_owned(), "1".to_owned()))
+            .op(8, Op::Return("x".to_owned())));
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec!["pause global@4"]);
+        let generator = match global.as_environment().unwrap().get_variable("it") {
+            Ok(Value::Object(generator)) => generator,
+            value => panic!("it is {:?}", value)
+        };
+        assert_eq!(runtime.resume(&generator), CompletionValue::Return(Value::Number(2.0)));
+        assert_eq!(recorder.log(), vec!["pause global@4"]);
+    }
+
+    #[test]
+    fn run_to_line_in_a_child_script() {
+        let (runtime, global, _debugger) = setup();
+        let recorder = Recorder::new();
+        let source = runtime.new_source(SourceBuilder::new("test.js", ""));
+        let script = runtime.new_script(&global, &source, call_script());
+        let step = run_to_line(&script, 2, Rc::new(Box::new(recorder.clone()))).unwrap();
+        runtime.execute(&script);
+        assert!(!step.is_active());
+        assert_eq!(recorder.log(), vec!["pause f@0"]);
+        assert_eq!(run_to_line(&script, 3, Rc::new(Box::new(recorder.clone()))).err(), Some(Error::LineNotValid));
+    }
+
+    /// Returns a script that defines functions `f` and `g`, and then calls `f`
+    /// and the function `h` on the same line as a debugger statement.
+    fn target_script() -> ScriptBuilder {
+        ScriptBuilder::new(1)
+            .function(1, "f", &["a"], ScriptBuilder::new(2)
+                .op(2, Op::Return("a".to_owned())))
+            .function(3, "g", &["a"], ScriptBuilder::new(4)
+                .op(4, Op::Return("a".to_owned())))
+            .op(6, Op::Var("h".to_owned(), "g".to_owned()))
+            .op(7, Op::Debugger)
+            .op(7, Op::Expression("f(1)".to_owned()))
+            .op(7, Op::Expression("h(2)".to_owned()))
+    }
+
+    /// Returns the object that is the value of the variable with the given
+    /// `name` in the given `global`.
+    fn variable(global: &Object<Reference>, name: &str) -> Object<Reference> {
+        match global.as_environment().unwrap().get_variable(name) {
+            Ok(Value::Object(object)) => object,
+            value => panic!("{} is {:?}", name, value)
+        }
+    }
+
+    #[test]
+    fn step_into_target_only_pauses_in_the_target() {
+        let (runtime, global, debugger) = setup();
+        let recorder = Recorder::new();
+        let (handler, stepping, target) = (recorder.clone(), debugger.clone(), global.clone());
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Starter(move |frame: &Frame<Reference>| {
+            step_into_target(&stepping, frame, &variable(&target, "g"), Rc::new(Box::new(handler.clone())));
+        })))));
+        debugger.set_enter_frame_handler(Some(Rc::new(Box::new(recorder.clone()))));
+        execute(&runtime, &global, target_script());
+        assert_eq!(recorder.log(), vec!["enter global@0", "enter f@0", "enter g@0", "pause g@0"]);
+    }
+
+    #[test]
+    fn step_into_a_bound_target_pauses_in_its_target_function() {
+        let runtime = Rc::new(Runtime::new());
+        let global = runtime.new_global();
+        let debugger = runtime.new_debugger();
+        debugger.add_debuggee(&global);
+        let recorder = Recorder::new();
+        let (handler, stepping, binder, target) = (recorder.clone(), debugger.clone(), runtime.clone(), global.clone());
+        debugger.set_debugger_statement_handler(Some(Rc::new(Box::new(Starter(move |frame: &Frame<Reference>| {
+            let bound = binder.bind_function(&variable(&target, "g"), &Value::Undefined, &[]);
+            target.as_environment().unwrap().set_variable("h", &Value::Object(bound.clone())).unwrap();
+            step_into_target(&stepping, frame, &bound, Rc::new(Box::new(handler.clone())));
+        })))));
+        let source = runtime.new_source(SourceBuilder::new("test.js", ""));
+        let script = runtime.new_script(&global, &source, target_script());
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec!["pause g@0"]);
+    }
+
+    #[test]
+    fn run_to_line_removes_its_breakpoints_before_pausing() {
+        let (runtime, global, _debugger) = setup();
+        let recorder = Recorder::new();
+        let source = runtime.new_source(SourceBuilder::new("test.js", ""));
+        let script = runtime.new_script(&global, &source, call_script());
+        let body = script.get_child_scripts()[0].clone();
+        assert!(body.get_breakpoints(0).unwrap().is_empty());
+        let handler = recorder.clone();
+        run_to_line(&script, 2, Rc::new(Box::new(Pauser(recorder.clone(), move |frame: &Frame<Reference>| {
+            let breakpoints = frame.script().unwrap().get_breakpoints(0).unwrap();
+            handler.record(format!("{} breakpoints", breakpoints.len()));
+        })))).unwrap();
+        assert_eq!(body.get_breakpoints(0).unwrap().len(), 1);
+        runtime.execute(&script);
+        assert_eq!(recorder.log(), vec!["pause f@0", "0 breakpoints"]);
+        assert!(body.get_breakpoints(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn run_to_line_pauses_once_in_a_recursive_call() {
+        let (runtime, global, _debugger) = setup();
+        let recorder = Recorder::new();
+        let source = runtime.new_source(SourceBuilder::new("test.js", ""));
+        let script = runtime.new_script(&global, &source, ScriptBuilder::new(1)
+            .function(1, "f", &["a"], ScriptBuilder::new(2)
+                .op(2, Op::Return("a && f(0)".to_owned())))
+            .op(4, Op::Return("f(1)".to_owned())));
+        let step = run_to_line(&script, 2, Rc::new(Box::new(recorder.clone()))).unwrap();
+        assert_eq!(runtime.execute(&script), CompletionValue::Return(Value::Number(0.0)));
+        assert!(!step.is_active());
+        assert_eq!(recorder.log(), vec!["pause f@0"]);
+    }
+}